@@ -0,0 +1,65 @@
+//! Reading and recording each day's `answers.txt` manifest (see [`common::answers`] for its
+//! format).
+
+use std::{fs, io, path::PathBuf};
+
+pub use common::answers::{parse, update, Expected};
+use common::{Answer, Part};
+
+use crate::Day;
+
+impl Day {
+    /// The input file an [`Expected`] answer is for.
+    pub fn expected_input(&self, expected: &Expected) -> PathBuf {
+        self.dir().join("inputs").join(&expected.file)
+    }
+
+    /// The day's `answers.txt` manifest.
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir().join("answers.txt")
+    }
+
+    /// Every answer recorded in the day's manifest, or none if it doesn't have one.
+    pub fn expected_answers(&self) -> Result<Vec<Expected>, String> {
+        let path = self.answers_path();
+        let manifest = match fs::read_to_string(&path) {
+            Ok(manifest) => manifest,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(format!("failed to read {}: {err}", path.display())),
+        };
+        parse(&manifest).map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    /// Records `answers` for one input file in the day's manifest, creating it if need be.
+    pub fn record_answers(&self, file: &str, answers: &[(Part, Answer)]) -> Result<(), String> {
+        let path = self.answers_path();
+        let manifest = match fs::read_to_string(&path) {
+            Ok(manifest) => manifest,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("failed to read {}: {err}", path.display())),
+        };
+        fs::write(&path, update(&manifest, file, answers))
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))
+    }
+
+    /// The recorded answer for one part of one input file, if there is one.
+    pub fn expected_answer(&self, file: &str, part: Part) -> Result<Option<Answer>, String> {
+        Ok(self
+            .expected_answers()?
+            .into_iter()
+            .find(|expected| expected.file == file && expected.part == part)
+            .map(|expected| expected.answer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DAYS;
+
+    #[test]
+    fn test_every_manifest_parses() {
+        for day in DAYS {
+            day.expected_answers().unwrap();
+        }
+    }
+}
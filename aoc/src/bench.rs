@@ -0,0 +1,355 @@
+//! Benchmarking for `aoc bench`: times the parse and solve steps of each part over many
+//! iterations, and compares the medians against a saved baseline. Baselines are plain text, one
+//! line per day and part with the parse and solve medians and p95s in nanoseconds:
+//!
+//! ```text
+//! # day part parse_median parse_p95 solve_median solve_p95
+//! 10 2 512000 530100 30100000 31000000
+//! ```
+//!
+//! With `--stream`, it instead compares reading an input whole against streaming it (see
+//! [`common::Solution::stream`]), end to end from the file.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use common::{alloc, input::Source, Answer, ParseError, Part};
+
+use crate::Day;
+
+/// Differences smaller than this are timer noise, however large they are relative to the
+/// baseline, and are never reported as regressions.
+pub const NOISE_FLOOR: Duration = Duration::from_micros(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        assert!(!samples.is_empty(), "need at least one sample");
+        samples.sort();
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100) - 1];
+        Stats {
+            median: percentile(50),
+            p95: percentile(95),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: Part,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Solves one part `iterations` times, after one untimed warm-up run.
+pub fn measure(
+    day: &Day,
+    part: Part,
+    input: &str,
+    iterations: usize,
+) -> Result<Measurement, ParseError> {
+    day.solve_timed(part, input)?;
+
+    let mut parse = Vec::with_capacity(iterations);
+    let mut solve = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (_, timings) = day.solve_timed(part, input)?;
+        parse.push(timings.parse);
+        solve.push(timings.solve);
+    }
+
+    Ok(Measurement {
+        day: day.number,
+        part,
+        parse: Stats::from_samples(&mut parse),
+        solve: Stats::from_samples(&mut solve),
+    })
+}
+
+/// One part of an input solved both ways: read into memory and parsed whole, or streamed. The
+/// peaks are the most heap memory each used at once, which is zero unless allocations are
+/// counted, and leave out the pages of a memory-mapped input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamComparison {
+    pub whole: Stats,
+    pub stream: Stats,
+    pub whole_peak: usize,
+    pub stream_peak: usize,
+}
+
+impl fmt::Display for StreamComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counted = self.whole_peak > 0 || self.stream_peak > 0;
+        let peak = |peak| match counted {
+            true => format!(" (peak {})", alloc::format_bytes(peak)),
+            false => String::new(),
+        };
+        let speedup = self.whole.median.as_secs_f64() / self.stream.median.as_secs_f64();
+        write!(
+            f,
+            "whole {}{}, streamed {}{}, {}",
+            format_duration(self.whole.median),
+            peak(self.whole_peak),
+            format_duration(self.stream.median),
+            peak(self.stream_peak),
+            match speedup >= 1.0 {
+                true => format!("{speedup:.1}x faster"),
+                false => format!("{:.1}x slower", 1.0 / speedup),
+            }
+        )
+    }
+}
+
+/// Solves one part of `source` whole and streamed `iterations` times each, timing everything
+/// from opening the input to the answer, after one untimed run of each that checks that they
+/// give the same answer.
+pub fn compare_stream(
+    day: &Day,
+    part: Part,
+    source: &Source,
+    iterations: usize,
+) -> Result<StreamComparison, String> {
+    let whole = || -> Result<Answer, String> {
+        let input = source.read()?;
+        day.solve(part, &input.text)
+            .map_err(|err| format!("failed to parse {source}: {err}"))
+    };
+    let stream = || day.stream(part, source);
+
+    let (whole_answer, whole_allocations) = alloc::measure(whole);
+    let (stream_answer, stream_allocations) = alloc::measure(stream);
+    let (whole_answer, stream_answer) = (whole_answer?, stream_answer?);
+    if whole_answer != stream_answer {
+        return Err(format!(
+            "{source}: part {part} streamed gave {stream_answer}, but {whole_answer} whole"
+        ));
+    }
+
+    let time = |solve: &dyn Fn() -> Result<Answer, String>| {
+        let mut samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let start = Instant::now();
+            solve()?;
+            samples.push(start.elapsed());
+        }
+        Ok::<_, String>(Stats::from_samples(&mut samples))
+    };
+    Ok(StreamComparison {
+        whole: time(&whole)?,
+        stream: time(&stream)?,
+        whole_peak: whole_allocations.peak,
+        stream_peak: stream_allocations.peak,
+    })
+}
+
+/// A step whose median got slower than the baseline allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Regression {
+    pub step: &'static str,
+    pub before: Duration,
+    pub after: Duration,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = self.after.as_secs_f64() / self.before.as_secs_f64() - 1.0;
+        write!(
+            f,
+            "{} {} -> {} (+{:.0}%)",
+            self.step,
+            format_duration(self.before),
+            format_duration(self.after),
+            change * 100.0
+        )
+    }
+}
+
+/// The steps of `current` whose median is more than `threshold` percent slower than in
+/// `baseline`.
+pub fn regressions(
+    current: &Measurement,
+    baseline: &Measurement,
+    threshold: f64,
+) -> Vec<Regression> {
+    [
+        ("parse", baseline.parse.median, current.parse.median),
+        ("solve", baseline.solve.median, current.solve.median),
+    ]
+    .into_iter()
+    .filter(|&(_, before, after)| {
+        after > before + NOISE_FLOOR
+            && after.as_secs_f64() > before.as_secs_f64() * (1.0 + threshold / 100.0)
+    })
+    .map(|(step, before, after)| Regression {
+        step,
+        before,
+        after,
+    })
+    .collect()
+}
+
+pub fn parse_baseline(text: &str) -> Result<Vec<Measurement>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| parse_baseline_line(line).map_err(|err| format!("line {}: {err}", i + 1)))
+        .collect()
+}
+
+fn parse_baseline_line(line: &str) -> Result<Measurement, String> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let [day, part, nanos @ ..] = fields.as_slice() else {
+        return Err(format!("expected a day and part, found `{}`", line.trim()));
+    };
+    let nanos = nanos
+        .iter()
+        .map(|n| n.parse().map(Duration::from_nanos))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("invalid duration: {err}"))?;
+    let &[parse_median, parse_p95, solve_median, solve_p95] = nanos.as_slice() else {
+        return Err(format!("expected 4 durations, found {}", nanos.len()));
+    };
+
+    Ok(Measurement {
+        day: day.parse().map_err(|_| format!("invalid day `{day}`"))?,
+        part: part.parse()?,
+        parse: Stats {
+            median: parse_median,
+            p95: parse_p95,
+        },
+        solve: Stats {
+            median: solve_median,
+            p95: solve_p95,
+        },
+    })
+}
+
+pub fn format_baseline(measurements: &[Measurement]) -> String {
+    let mut text = "# day part parse_median parse_p95 solve_median solve_p95\n".to_string();
+    for m in measurements {
+        text += &format!(
+            "{} {} {} {} {} {}\n",
+            m.day,
+            m.part,
+            m.parse.median.as_nanos(),
+            m.parse.p95.as_nanos(),
+            m.solve.median.as_nanos(),
+            m.solve.p95.as_nanos()
+        );
+    }
+    text
+}
+
+/// Formats a duration with a sensible unit and about three significant digits, e.g. `1.23ms`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    let (value, unit) = match nanos {
+        n if n < 1e3 => (n, "ns"),
+        n if n < 1e6 => (n / 1e3, "µs"),
+        n if n < 1e9 => (n / 1e6, "ms"),
+        n => (n / 1e9, "s"),
+    };
+    let decimals = match value {
+        v if v < 10.0 => 2,
+        v if v < 100.0 => 1,
+        _ => 0,
+    };
+    format!("{value:.decimals$}{unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn measurement(parse: u64, solve: u64) -> Measurement {
+        Measurement {
+            day: 10,
+            part: Part::Two,
+            parse: Stats {
+                median: ms(parse),
+                p95: ms(parse + 1),
+            },
+            solve: Stats {
+                median: ms(solve),
+                p95: ms(solve + 1),
+            },
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut samples = (1..=100).rev().map(ms).collect::<Vec<_>>();
+        let stats = Stats::from_samples(&mut samples);
+        assert_eq!(stats.median, ms(50));
+        assert_eq!(stats.p95, ms(95));
+
+        assert_eq!(Stats::from_samples(&mut [ms(3)]).p95, ms(3));
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = measurement(10, 100);
+        assert_eq!(regressions(&measurement(10, 109), &baseline, 10.0), vec![]);
+        assert_eq!(
+            regressions(&measurement(12, 100), &baseline, 10.0),
+            vec![Regression {
+                step: "parse",
+                before: ms(10),
+                after: ms(12),
+            }]
+        );
+        assert_eq!(regressions(&measurement(5, 50), &baseline, 10.0), vec![]);
+    }
+
+    #[test]
+    fn test_regressions_ignore_noise() {
+        let baseline = Measurement {
+            parse: Stats {
+                median: Duration::from_nanos(100),
+                p95: Duration::from_nanos(100),
+            },
+            ..measurement(0, 100)
+        };
+        let current = Measurement {
+            parse: Stats {
+                median: Duration::from_nanos(300),
+                p95: Duration::from_nanos(300),
+            },
+            ..baseline
+        };
+        assert_eq!(regressions(&current, &baseline, 10.0), vec![]);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let measurements = vec![measurement(1, 2), measurement(3, 40)];
+        assert_eq!(
+            parse_baseline(&format_baseline(&measurements)),
+            Ok(measurements)
+        );
+        assert_eq!(
+            parse_baseline("10 2 1 2 3"),
+            Err("line 1: expected 4 durations, found 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(512)), "512ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_234)), "1.23µs");
+        assert_eq!(format_duration(Duration::from_micros(30_140)), "30.1ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.00s");
+    }
+}
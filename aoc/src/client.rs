@@ -0,0 +1,276 @@
+//! Talking to the Advent of Code site. Requests need the `session` cookie of a logged-in browser,
+//! which is read from the `AOC_SESSION` environment variable or from a config file of `key =
+//! value` lines:
+//!
+//! ```text
+//! session = 53616c7465645f5f...
+//! base_url = https://adventofcode.com
+//! year = 2023
+//! min_interval_secs = 5
+//! ```
+//!
+//! The file is `$AOC_CONFIG` if that's set, and `~/.config/aoc/config` otherwise. Environment
+//! variables (`AOC_SESSION`, `AOC_BASE_URL`) take precedence over the file.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::workspace_root;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u16 = 2023;
+/// The least time between two requests to the site, across runs of the tool.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+const USER_AGENT: &str = "github.com/thelastnode/advent-of-code-2023 aoc runner";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+    pub year: u16,
+    pub min_interval: Duration,
+    /// Where state shared between runs lives, like the time of the last request.
+    pub state_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            year: DEFAULT_YEAR,
+            min_interval: DEFAULT_MIN_INTERVAL,
+            state_dir: workspace_root().join(".aoc"),
+        }
+    }
+}
+
+impl Config {
+    /// The defaults, overridden by the config file and then by the environment.
+    pub fn load() -> Result<Config, String> {
+        let mut config = Config::default();
+
+        let path = match env::var_os("AOC_CONFIG") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => env::var_os("HOME").map(|home| Path::new(&home).join(".config/aoc/config")),
+        };
+        if let Some(path) = path {
+            match fs::read_to_string(&path) {
+                Ok(text) => config
+                    .apply_file(&text)
+                    .map_err(|err| format!("{}: {err}", path.display()))?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(format!("failed to read {}: {err}", path.display())),
+            }
+        }
+
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session.trim().to_string());
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        Ok(config)
+    }
+
+    fn apply_file(&mut self, text: &str) -> Result<(), String> {
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| format!("line {}: {message}", i + 1);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected `key = value`"))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "session" => self.session = Some(value.to_string()),
+                "base_url" => self.base_url = value.to_string(),
+                "year" => self.year = value.parse().map_err(|_| error("invalid year"))?,
+                "min_interval_secs" => {
+                    self.min_interval = value
+                        .parse()
+                        .ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                        .ok_or_else(|| error("invalid interval"))?;
+                }
+                key => return Err(error(&format!("unknown key `{key}`"))),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether [`Client::fetch_input`] had to download the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+pub struct Client {
+    config: Config,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(config: Config) -> Client {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+        Client { config, agent }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Downloads a day's input to `dest`, unless it's already there. The input is written to a
+    /// temporary file next to `dest` and renamed into place, so an interrupted download never
+    /// leaves a partial input behind to be taken for a cached one.
+    pub fn fetch_input(&self, day: u8, dest: &Path) -> Result<Fetched, String> {
+        if dest.exists() {
+            return Ok(Fetched::Cached);
+        }
+
+        let url = format!("{}/{}/day/{day}/input", self.base_url(), self.config.year);
+        let request = self.authorized(self.agent.get(&url))?;
+        let input = self
+            .send(request, None)?
+            .into_string()
+            .map_err(|err| format!("failed to read the response from {url}: {err}"))?;
+
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+        }
+        let name = dest.file_name().unwrap_or_default().to_string_lossy();
+        let temp = dest.with_file_name(format!(".{name}.{}.tmp", process::id()));
+        fs::write(&temp, input)
+            .and_then(|()| fs::rename(&temp, dest))
+            .map_err(|err| {
+                let _ = fs::remove_file(&temp);
+                format!("failed to write {}: {err}", dest.display())
+            })?;
+        Ok(Fetched::Downloaded)
+    }
+
+    pub(crate) fn agent(&self) -> &ureq::Agent {
+        &self.agent
+    }
+
+    pub(crate) fn base_url(&self) -> &str {
+        self.config.base_url.trim_end_matches('/')
+    }
+
+    pub(crate) fn authorized(&self, request: ureq::Request) -> Result<ureq::Request, String> {
+        let session = self.config.session.as_deref().ok_or(
+            "no session token; set AOC_SESSION or `session` in the config file".to_string(),
+        )?;
+        Ok(request.set("Cookie", &format!("session={session}")))
+    }
+
+    /// Sends a request, with `form` as its body if there is one, once enough time has passed
+    /// since the last one. HTTP errors are turned into messages.
+    pub(crate) fn send(
+        &self,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<ureq::Response, String> {
+        self.wait_for_turn()?;
+        let result = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+        match result {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                let body = body.trim();
+                Err(match status {
+                    400 | 401 | 403 => format!(
+                        "{} returned {status}; is the session token still valid?",
+                        self.config.base_url
+                    ),
+                    404 => format!(
+                        "{} returned 404; is the puzzle unlocked yet?",
+                        self.config.base_url
+                    ),
+                    _ if body.is_empty() => format!("{} returned {status}", self.config.base_url),
+                    _ => format!("{} returned {status}: {body}", self.config.base_url),
+                })
+            }
+            Err(err) => Err(format!("request failed: {err}")),
+        }
+    }
+
+    /// Sleeps until `min_interval` has passed since the last request made by any run of the tool,
+    /// then records this one.
+    fn wait_for_turn(&self) -> Result<(), String> {
+        let path = self.config.state_dir.join("last-request");
+        let last = fs::read_to_string(&path)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+
+        if let Some(last) = last {
+            let elapsed = SystemTime::now()
+                .duration_since(last)
+                .unwrap_or(Duration::ZERO);
+            if let Some(remaining) = self.config.min_interval.checked_sub(elapsed) {
+                eprintln!(
+                    "waiting {:.1}s before the next request",
+                    remaining.as_secs_f64()
+                );
+                thread::sleep(remaining);
+            }
+        }
+
+        fs::create_dir_all(&self.config.state_dir).map_err(|err| {
+            format!(
+                "failed to create {}: {err}",
+                self.config.state_dir.display()
+            )
+        })?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        fs::write(&path, now.to_string())
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_file() {
+        let mut config = Config::default();
+        config
+            .apply_file("# mine\nsession = abc123\nbase_url=http://localhost:8000/\nyear = 2022\nmin_interval_secs = 0.5\n")
+            .unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc123"));
+        assert_eq!(config.base_url, "http://localhost:8000/");
+        assert_eq!(config.year, 2022);
+        assert_eq!(config.min_interval, Duration::from_millis(500));
+
+        assert_eq!(
+            config.apply_file("session abc"),
+            Err("line 1: expected `key = value`".to_string())
+        );
+        assert_eq!(
+            config.apply_file("\ntoken = abc"),
+            Err("line 2: unknown key `token`".to_string())
+        );
+    }
+}
@@ -0,0 +1,234 @@
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod lookalike;
+pub mod run_all;
+pub mod scaffold;
+pub mod serve;
+pub mod submit;
+pub mod watch;
+
+use std::path::{Path, PathBuf};
+
+pub use common::Part;
+use common::{
+    bytes::{self, Lines},
+    input::Source,
+    rng::Rng,
+    trace::{self, Event, Level},
+    Answer, Extras, ParseError, Solution, StepAllocations, Timings,
+};
+
+type SolveFn<T> = fn(Part, &str) -> Result<(Answer, T), ParseError>;
+
+pub struct Day {
+    pub number: u8,
+    pub name: &'static str,
+    solve_timed: SolveFn<Timings>,
+    solve_counted: SolveFn<StepAllocations>,
+    extras: fn(Part, &str) -> Result<Extras, ParseError>,
+    check: fn(&str, Option<Part>) -> Vec<ParseError>,
+    generate: fn(&mut Rng, usize) -> Option<String>,
+    lookalike: fn(&str, &mut Rng) -> Result<Option<String>, ParseError>,
+    stream: fn(Part, &mut Lines<'_>) -> Option<Result<Answer, ParseError>>,
+}
+
+impl Day {
+    pub fn get(number: u8) -> Option<&'static Day> {
+        DAYS.iter().find(|day| day.number == number)
+    }
+
+    /// The day's crate directory, e.g. `<workspace>/day10`.
+    pub fn dir(&self) -> PathBuf {
+        workspace_root().join(self.name)
+    }
+
+    /// Resolves an input path given on the command line. Paths that don't exist relative to the
+    /// current directory are looked up relative to the day's crate, so `inputs/example1.txt`
+    /// works from anywhere. Without a path, the day's `inputs/input.txt` is used.
+    pub fn input_path(&self, path: Option<&str>) -> PathBuf {
+        match path {
+            None => self.dir().join("inputs").join("input.txt"),
+            Some(path) if Path::new(path).exists() => PathBuf::from(path),
+            Some(path) => self.dir().join(path),
+        }
+    }
+
+    /// The inputs named by a command-line argument, which is resolved like in
+    /// [`Day::input_path`] and then expanded like in [`Source::expand`], except that `-` is
+    /// always stdin.
+    pub fn sources(&self, arg: Option<&str>) -> Result<Vec<Source>, String> {
+        match arg {
+            Some("-") => Ok(vec![Source::Stdin]),
+            arg => Source::expand(&self.input_path(arg)),
+        }
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> Result<Answer, ParseError> {
+        self.solve_timed(part, input).map(|(answer, _)| answer)
+    }
+
+    pub fn solve_timed(&self, part: Part, input: &str) -> Result<(Answer, Timings), ParseError> {
+        (self.solve_timed)(part, input)
+    }
+
+    /// Solves a part like [`Day::solve_timed`], also returning the trace events it recorded at
+    /// `level` or above. See [`common::trace`].
+    pub fn explain(
+        &self,
+        part: Part,
+        input: &str,
+        level: Level,
+    ) -> (Result<(Answer, Timings), ParseError>, Vec<Event>) {
+        trace::capture(level, || self.solve_timed(part, input))
+    }
+
+    /// The extra values the day reports for a part. See [`common::Solution::extras`].
+    pub fn extras(&self, part: Part, input: &str) -> Result<Extras, ParseError> {
+        (self.extras)(part, input)
+    }
+
+    /// Every problem with an input, found without solving it, including those only `part` trips
+    /// over if given. See [`common::check_input`].
+    pub fn check(&self, input: &str, part: Option<Part>) -> Vec<ParseError> {
+        (self.check)(input, part)
+    }
+
+    /// Solves a part, counting what its parse and solve steps allocate. See
+    /// [`common::solve_counted`].
+    pub fn solve_counted(
+        &self,
+        part: Part,
+        input: &str,
+    ) -> Result<(Answer, StepAllocations), ParseError> {
+        (self.solve_counted)(part, input)
+    }
+
+    /// A random input of roughly `size` lines or rows, the same for every run with the same
+    /// `seed`, or `None` if the day has no generator. See [`common::Solution::generate`]. Like
+    /// the saved inputs, it has no trailing newline.
+    pub fn generate(&self, seed: u64, size: usize) -> Option<String> {
+        let mut input = (self.generate)(&mut Rng::new(seed), size)?;
+        input.truncate(input.trim_end_matches('\n').len());
+        Some(input)
+    }
+
+    /// A synthetic input shaped like `input`, made from `seed`, or `None` if the day can't make
+    /// one. See [`common::Solution::lookalike`]. Like [`Day::generate`], it has no trailing
+    /// newline.
+    pub fn lookalike(&self, input: &str, seed: u64) -> Result<Option<String>, ParseError> {
+        let Some(mut lookalike) = (self.lookalike)(input, &mut Rng::new(seed))? else {
+            return Ok(None);
+        };
+        lookalike.truncate(lookalike.trim_end_matches('\n').len());
+        Ok(Some(lookalike))
+    }
+
+    /// Solves a part from `lines` as they're read, or `None` if the day needs the whole input at
+    /// once. See [`common::Solution::stream`].
+    pub fn stream_lines(
+        &self,
+        part: Part,
+        lines: &mut Lines<'_>,
+    ) -> Option<Result<Answer, ParseError>> {
+        (self.stream)(part, lines)
+    }
+
+    /// Whether the day can solve its input a line at a time, found by streaming an empty one.
+    pub fn streams(&self) -> bool {
+        Part::ALL.iter().all(|&part| {
+            self.stream_lines(part, &mut Lines::from_bytes(b""))
+                .is_some()
+        })
+    }
+
+    /// Solves a part by streaming `source` a line at a time instead of reading it into memory,
+    /// through a memory map if it's a plain file. See [`common::bytes::open`].
+    pub fn stream(&self, part: Part, source: &Source) -> Result<Answer, String> {
+        let mut lines = bytes::open(source)?;
+        let answer = self.stream_lines(part, &mut lines).ok_or_else(|| {
+            format!(
+                "day {} can't stream its input, since it needs all of it at once",
+                self.number
+            )
+        })?;
+        if let Some(err) = lines.take_error() {
+            return Err(format!("failed to read {source}: {err}"));
+        }
+        answer.map_err(|err| format!("failed to parse {source}: {err}"))
+    }
+}
+
+pub fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("aoc crate should live inside the workspace")
+}
+
+macro_rules! days {
+    ($($number:literal => $krate:ident::$solution:ident),* $(,)?) => {
+        pub static DAYS: &[Day] = &[$(
+            Day {
+                number: $number,
+                name: stringify!($krate),
+                solve_timed: common::solve_timed::<$krate::$solution>,
+                solve_counted: common::solve_counted::<$krate::$solution>,
+                extras: common::solve_extras::<$krate::$solution>,
+                check: common::check_input::<$krate::$solution>,
+                generate: <$krate::$solution as Solution>::generate,
+                lookalike: common::lookalike_input::<$krate::$solution>,
+                stream: <$krate::$solution as Solution>::stream,
+            },
+        )*];
+    };
+}
+
+days! {
+    1 => day01::Day01,
+    2 => day02::Day02,
+    3 => day03::Day03,
+    4 => day04::Day04,
+    5 => day05::Day05,
+    6 => day06::Day06,
+    7 => day07::Day07,
+    8 => day08::Day08,
+    9 => day09::Day09,
+    10 => day10::Day10,
+    11 => day11::Day11,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// However an example was saved, every day should read it the same way.
+    #[test]
+    fn test_days_accept_differently_saved_inputs() {
+        for day in DAYS {
+            let examples = day.expected_answers().unwrap();
+            for expected in examples.iter().filter(|e| e.file.starts_with("example")) {
+                let text = fs::read_to_string(day.expected_input(expected)).unwrap();
+                let variants = [
+                    format!("{text}\n"),
+                    format!("{text}\n\n\n"),
+                    format!("\u{feff}{text}"),
+                    text.replace('\n', "\r\n") + "\r\n",
+                    text.replace('\n', " \n") + "\t\n",
+                ];
+
+                for variant in &variants {
+                    assert_eq!(
+                        day.solve(expected.part, variant),
+                        Ok(expected.answer),
+                        "{} {} part {} with {variant:?}",
+                        day.name,
+                        expected.file,
+                        expected.part
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! `aoc lookalike`: synthetic stand-ins for real puzzle inputs, which puzzle authors ask people
+//! not to publish. A day's [`common::Solution::lookalike`] makes an input shaped like the real
+//! one, and [`replace`] swaps it in and records its answers, so the regression tests keep
+//! covering inputs of the real size and shape.
+
+use std::{fs, path::Path, time::Duration};
+
+use common::{
+    budget::{self, Budget},
+    input::Source,
+    Answer, Part,
+};
+
+use crate::Day;
+
+/// How long solving a look-alike may take before it's given up on.
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A look-alike of `input`, made from `seed`.
+pub fn make(day: &Day, input: &str, seed: u64) -> Result<String, String> {
+    day.lookalike(input, seed)
+        .map_err(|err| format!("failed to parse the input: {err}"))?
+        .ok_or_else(|| format!("day {} can't make look-alike inputs", day.number))
+}
+
+/// Checks `lookalike` and solves the given parts of it, failing if there's anything wrong with it.
+pub fn solve(day: &Day, lookalike: &str, parts: &[Part]) -> Result<Vec<(Part, Answer)>, String> {
+    let mut problems = parts
+        .iter()
+        .flat_map(|&part| day.check(lookalike, Some(part)));
+    if let Some(problem) = problems.next() {
+        return Err(format!("the look-alike isn't valid: {problem}"));
+    }
+    let budget = Budget {
+        time: Some(SOLVE_TIMEOUT),
+        ..Budget::default()
+    };
+    parts
+        .iter()
+        .map(|&part| {
+            let answer = budget::run(&budget, || day.solve(part, lookalike))
+                .map_err(|exceeded| format!("part {part} of the look-alike {exceeded}"))?
+                .map_err(|err| format!("failed to parse the look-alike: {err}"))?;
+            Ok((part, answer))
+        })
+        .collect()
+}
+
+/// Replaces the input at `path`, which must be in the day's `inputs/` folder, with a look-alike
+/// made from `seed`, and records its answers in `answers.txt`. The parts recorded are the ones
+/// that were recorded for the old input, or both if there were none. Nothing is written unless
+/// the look-alike checks out and solves.
+pub fn replace(day: &Day, path: &Path, seed: u64) -> Result<Vec<(Part, Answer)>, String> {
+    let inputs = day.dir().join("inputs");
+    let in_inputs = path
+        .parent()
+        .and_then(|parent| parent.canonicalize().ok())
+        .is_some_and(|parent| inputs.canonicalize().is_ok_and(|inputs| parent == inputs));
+    let Some(file) = path.file_name().and_then(|file| file.to_str()) else {
+        return Err(format!("{} isn't a file", path.display()));
+    };
+    if !in_inputs {
+        return Err(format!(
+            "only inputs in {} can be replaced, since their answers are recorded there",
+            inputs.display()
+        ));
+    }
+
+    let input = Source::File(path.to_path_buf()).read()?;
+    let lookalike = make(day, &input.text, seed)?;
+    let mut parts = day
+        .expected_answers()?
+        .into_iter()
+        .filter(|expected| expected.file == file)
+        .map(|expected| expected.part)
+        .collect::<Vec<_>>();
+    if parts.is_empty() {
+        parts = Part::ALL.to_vec();
+    }
+    let answers = solve(day, &lookalike, &parts)?;
+
+    fs::write(path, &lookalike)
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    day.record_answers(file, &answers)?;
+    Ok(answers)
+}
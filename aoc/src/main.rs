@@ -0,0 +1,923 @@
+use std::{
+    env, fs,
+    net::TcpListener,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process::ExitCode,
+    thread,
+    time::Duration,
+};
+
+use common::{
+    budget::{self, panic_message, Budget, Exceeded},
+    input::{self, Source},
+    trace::{Event, Level},
+    Answer, ParseError, Timings, Value,
+};
+
+use aoc::{
+    bench::{self, Measurement},
+    client::{Client, Config, Fetched},
+    scaffold,
+    serve::Limits,
+    submit::Verdict,
+    workspace_root, Day, Part, DAYS,
+};
+
+const USAGE: &str = "\
+usage:
+    aoc run <day> [part] [input] [--format text|json] [--explain[=info|debug]] [--check]
+            [--max-steps N] [--timeout SECS] [--stream]
+    aoc run --all [--format text|json] [--explain[=info|debug]] [--check]
+            [--max-steps N] [--timeout SECS]
+    aoc all [--jobs N] [--timeout SECS] [--max-steps N]
+    aoc bench [day] [part] [--iterations N] [--threshold PERCENT] [--baseline FILE] [--save]
+    aoc bench --stream [day] [part] [input] [--iterations N]
+    aoc alloc [day] [part] [input]
+    aoc new <day>
+    aoc fetch <day>
+    aoc submit <day> <part> [input]
+    aoc gen <day> [--seed N] [--size M]
+    aoc lookalike <day> [input] [--seed N] [--write]
+    aoc watch <day> [part] [--release] [--timeout SECS]
+    aoc serve [--port N] [--max-body BYTES] [--timeout SECS] [--max-connections N]
+
+<input> is looked up in the day's crate if it doesn't exist relative to the current directory.
+It can be a file (gzipped if it ends in `.gz`), a directory to run every `*.txt` file in, or `-`
+for stdin. Without one, input is read from stdin if it's piped, or from the day's
+inputs/input.txt otherwise.";
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: common::alloc::CountingAllocator = common::alloc::CountingAllocator;
+
+/// Why a command failed.
+enum Error {
+    /// The arguments were wrong, so the usage is worth showing.
+    Usage(String),
+    /// Something went wrong running the command.
+    Failed(String),
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Failed(message)
+    }
+}
+
+fn usage(message: impl Into<String>) -> Error {
+    Error::Usage(message.into())
+}
+
+fn parse_day(day: &str) -> Result<&'static Day, Error> {
+    day.parse()
+        .ok()
+        .and_then(Day::get)
+        .ok_or_else(|| usage(format!("unknown day `{day}`")))
+}
+
+fn parse_part(part: &str) -> Result<Part, Error> {
+    part.parse().map_err(usage)
+}
+
+/// The input argument for commands that take one, standing in `-` for piped stdin.
+fn input_arg(arg: Option<&str>) -> Option<&str> {
+    arg.or_else(|| input::stdin_is_piped().then_some("-"))
+}
+
+/// Reads every input `arg` names up front, so that stdin can be solved for more than one part.
+fn read_inputs(day: &Day, arg: Option<&str>) -> Result<Vec<(Source, String)>, String> {
+    day.sources(arg)?
+        .into_iter()
+        .map(|source| {
+            let input = source.read()?;
+            for change in &input.changes {
+                eprintln!("note: {source}: {change}");
+            }
+            Ok((source, input.text.into_owned()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One JSON object per line for each run, with the answer, timings and the day's extras.
+    Json,
+}
+
+/// Removes `--format <text|json>` from `args`, if it's there.
+fn take_format(args: &mut Vec<&str>) -> Result<Format, Error> {
+    let Some(i) = args.iter().position(|&arg| arg == "--format") else {
+        return Ok(Format::Text);
+    };
+    let format = match args.get(i + 1) {
+        Some(&"text") => Format::Text,
+        Some(&"json") => Format::Json,
+        Some(format) => {
+            return Err(usage(format!(
+                "unknown format `{format}`, expected text or json"
+            )))
+        }
+        None => return Err(usage("missing value for `--format`")),
+    };
+    args.drain(i..=i + 1);
+    Ok(format)
+}
+
+/// Removes `--explain` or `--explain=<level>` from `args`, returning the level to trace at if it
+/// was there.
+fn take_explain(args: &mut Vec<&str>) -> Result<Option<Level>, Error> {
+    let Some(i) = args.iter().position(|arg| arg.starts_with("--explain")) else {
+        return Ok(None);
+    };
+    let level = match args.remove(i) {
+        "--explain" => Level::Info,
+        arg => match arg.strip_prefix("--explain=") {
+            Some(level) => level.parse().map_err(usage)?,
+            None => return Err(usage(format!("unknown option `{arg}`"))),
+        },
+    };
+    Ok(Some(level))
+}
+
+fn parse_max_steps(value: &str) -> Result<u64, Error> {
+    value
+        .parse()
+        .ok()
+        .filter(|&steps| steps > 0)
+        .ok_or_else(|| usage("`--max-steps` should be a positive number"))
+}
+
+/// Removes `--max-steps <N>` and `--timeout <SECS>` from `args`, returning the budget they set.
+fn take_budget(args: &mut Vec<&str>) -> Result<Budget, Error> {
+    let mut budget = Budget::default();
+    while let Some(i) = args
+        .iter()
+        .position(|&arg| arg == "--max-steps" || arg == "--timeout")
+    {
+        let Some(&value) = args.get(i + 1) else {
+            return Err(usage(format!("missing value for `{}`", args[i])));
+        };
+        match args[i] {
+            "--max-steps" => budget.steps = Some(parse_max_steps(value)?),
+            _ => {
+                budget.time = value
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .filter(|time| !time.is_zero());
+                if budget.time.is_none() {
+                    return Err(usage("`--timeout` should be a positive number of seconds"));
+                }
+            }
+        }
+        args.drain(i..=i + 1);
+    }
+    Ok(budget)
+}
+
+/// Solves one part, tracing it at `explain`'s level if there is one.
+fn solve_part(
+    day: &Day,
+    part: Part,
+    input: &str,
+    explain: Option<Level>,
+) -> (Result<(Answer, Timings), ParseError>, Vec<Event>) {
+    match explain {
+        Some(level) => day.explain(part, input, level),
+        None => (day.solve_timed(part, input), Vec::new()),
+    }
+}
+
+fn exceeded_message(source: &Source, part: Part, exceeded: Exceeded) -> String {
+    format!("{source}: part {part} {exceeded}")
+}
+
+fn json_run(
+    day: &Day,
+    part: Part,
+    source: &Source,
+    input: &str,
+    explain: Option<Level>,
+    budget: &Budget,
+) -> Result<(), String> {
+    let mut fields = vec![
+        ("day", day.number.into()),
+        ("part", part.number().into()),
+        ("input", source.to_string().into()),
+    ];
+    // The extras can take as long as the answer, so they count against the budget too.
+    let run = || {
+        budget::run(budget, || {
+            let (run, events) = solve_part(day, part, input, explain);
+            let run = run.and_then(|(answer, timings)| {
+                let extras = day.extras(part, input)?;
+                Ok((answer, timings, extras))
+            });
+            (run, events)
+        })
+    };
+    // A panic is reported like any other error, so that there's always a line to read back.
+    let run = panic::catch_unwind(AssertUnwindSafe(run)).map_err(panic_message);
+    let result = match run {
+        Ok(Ok((Ok((answer, timings, extras)), events))) => {
+            fields.push(("answer", answer.value().into()));
+            fields.push((
+                "timings",
+                Value::Object(vec![
+                    ("parse_ns", (timings.parse.as_nanos() as u64).into()),
+                    ("solve_ns", (timings.solve.as_nanos() as u64).into()),
+                ]),
+            ));
+            fields.push(("extras", Value::Object(extras)));
+            if explain.is_some() {
+                let events = events.iter().map(|event| event.to_value()).collect();
+                fields.push(("explain", Value::List(events)));
+            }
+            Ok(())
+        }
+        Ok(Ok((Err(err), _))) => {
+            fields.push(("error", err.to_string().into()));
+            Err(format!("failed to parse {source}: {err}"))
+        }
+        Ok(Err(exceeded)) => {
+            fields.push(("error", exceeded.to_string().into()));
+            Err(exceeded_message(source, part, exceeded))
+        }
+        Err(message) => {
+            fields.push(("error", format!("panicked: {message}").into()));
+            Err(format!("{source}: part {part} panicked: {message}"))
+        }
+    };
+    println!("{}", Value::Object(fields).to_json());
+    result
+}
+
+/// Checks every input `arg` names without solving it, for `part` if given, and reports whether
+/// they were all valid.
+fn check_day(
+    day: &Day,
+    part: Option<Part>,
+    arg: Option<&str>,
+    format: Format,
+) -> Result<bool, String> {
+    let mut valid = true;
+    for (source, input) in read_inputs(day, arg)? {
+        let problems = day.check(&input, part);
+        valid &= problems.is_empty();
+        if format == Format::Json {
+            let problems = problems
+                .iter()
+                .map(|problem| {
+                    Value::Object(vec![
+                        ("line", problem.line.into()),
+                        ("column", problem.column.into()),
+                        ("expected", problem.expected.as_str().into()),
+                    ])
+                })
+                .collect();
+            let fields = vec![
+                ("day", day.number.into()),
+                ("input", source.to_string().into()),
+                ("problems", Value::List(problems)),
+            ];
+            println!("{}", Value::Object(fields).to_json());
+            continue;
+        }
+
+        for problem in &problems {
+            println!("{source}: {problem}");
+        }
+        match problems.len() {
+            0 => println!("Day {} ({source}): ok", day.number),
+            1 => println!("Day {} ({source}): 1 problem", day.number),
+            count => println!("Day {} ({source}): {count} problems", day.number),
+        }
+    }
+    Ok(valid)
+}
+
+fn run_day(
+    day: &Day,
+    parts: &[Part],
+    arg: Option<&str>,
+    format: Format,
+    explain: Option<Level>,
+    budget: &Budget,
+) -> Result<(), String> {
+    let inputs = read_inputs(day, arg)?;
+    for &part in parts {
+        for (source, input) in &inputs {
+            if format == Format::Json {
+                json_run(day, part, source, input, explain, budget)?;
+                continue;
+            }
+
+            let (run, events) = budget::run(budget, || solve_part(day, part, input, explain))
+                .map_err(|exceeded| exceeded_message(source, part, exceeded))?;
+            let (answer, _) = run.map_err(|err| format!("failed to parse {source}: {err}"))?;
+            match inputs.len() {
+                1 => println!("Day {}, part {part}: {answer}", day.number),
+                _ => println!("Day {}, part {part} ({source}): {answer}", day.number),
+            }
+            for event in &events {
+                println!("    {event}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Like [`run_day`], but streams each input a line at a time rather than reading it whole.
+fn stream_day(day: &Day, parts: &[Part], arg: Option<&str>, budget: &Budget) -> Result<(), String> {
+    let sources = day.sources(arg)?;
+    if parts.len() > 1 && sources.contains(&Source::Stdin) {
+        return Err("stdin can only be streamed once; give a part or a file".to_string());
+    }
+    for &part in parts {
+        for source in &sources {
+            let answer = budget::run(budget, || day.stream(part, source))
+                .map_err(|exceeded| exceeded_message(source, part, exceeded))??;
+            match sources.len() {
+                1 => println!("Day {}, part {part}: {answer}", day.number),
+                _ => println!("Day {}, part {part} ({source}): {answer}", day.number),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn count_allocations(
+    days: &[&Day],
+    parts: &[Part],
+    input_path: Option<&str>,
+) -> Result<(), String> {
+    if !cfg!(feature = "count-allocations") {
+        return Err(
+            "allocation counting is disabled; rebuild with `--features count-allocations`"
+                .to_string(),
+        );
+    }
+
+    for day in days {
+        let inputs = read_inputs(day, input_path)?;
+        for &part in parts {
+            for (source, input) in &inputs {
+                let (_, allocations) = day
+                    .solve_counted(part, input)
+                    .map_err(|err| format!("failed to parse {source}: {err}"))?;
+                match inputs.len() {
+                    1 => println!("Day {}, part {part}:", day.number),
+                    _ => println!("Day {}, part {part} ({source}):", day.number),
+                }
+                println!("    parse: {}", allocations.parse);
+                println!("    solve: {}", allocations.solve);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_day_number(day: &str) -> Result<u8, Error> {
+    day.parse()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| usage(format!("invalid day `{day}`, expected 1 to 25")))
+}
+
+fn new_day(day: &str) -> Result<(), Error> {
+    let number = parse_day_number(day)?;
+    for path in scaffold::new_day(workspace_root(), number)? {
+        println!("wrote {}", path.display());
+    }
+    println!(
+        "\nnext: `aoc fetch {number}`, paste the example into day{number:02}/inputs/example1.txt \
+         and replace the placeholder answers in day{number:02}/answers.txt with its real ones"
+    );
+    Ok(())
+}
+
+fn fetch(day: &str) -> Result<(), Error> {
+    let number = parse_day_number(day)?;
+    let dest = match Day::get(number) {
+        Some(day) => day.input_path(None),
+        None => workspace_root().join(format!("day{number:02}/inputs/input.txt")),
+    };
+
+    match Client::new(Config::load()?).fetch_input(number, &dest)? {
+        Fetched::Cached => println!("{} already exists, not refetching", dest.display()),
+        Fetched::Downloaded => println!("saved day {number}'s input to {}", dest.display()),
+    }
+    Ok(())
+}
+
+fn submit(day: &Day, part: Part, input_path: Option<&str>) -> Result<ExitCode, String> {
+    let [(source, input)] = read_inputs(day, input_path)?
+        .try_into()
+        .map_err(|_| "can only submit the answer for one input".to_string())?;
+    let answer = day
+        .solve(part, &input)
+        .map_err(|err| format!("failed to parse {source}: {err}"))?;
+    println!("Day {}, part {part}: submitting {answer}", day.number);
+
+    let verdict = Client::new(Config::load()?).submit(day.number, part, answer)?;
+    match verdict {
+        Verdict::Right => println!("right answer!"),
+        Verdict::Wrong => println!("wrong answer"),
+        Verdict::TooHigh => println!("wrong answer: too high"),
+        Verdict::TooLow => println!("wrong answer: too low"),
+        Verdict::Wait(wait) => println!("submitted too recently; wait {}s", wait.as_secs()),
+        Verdict::WrongLevel => println!("not the part being solved; is it already complete?"),
+    }
+    Ok(match verdict {
+        Verdict::Right => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    })
+}
+
+/// `aoc gen`: prints a random input for a day.
+fn generate(day: &Day, args: &[&str]) -> Result<(), Error> {
+    let mut seed = 0;
+    let mut size = 20;
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| usage(format!("missing value for `{arg}`")))?;
+        match arg {
+            "--seed" => {
+                seed = value
+                    .parse()
+                    .map_err(|_| usage("`--seed` should be a non-negative number"))?;
+            }
+            "--size" => {
+                size = value
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| usage("`--size` should be a positive number"))?;
+            }
+            _ => return Err(usage(format!("unknown option `{arg}`"))),
+        }
+    }
+
+    let input = day
+        .generate(seed, size)
+        .ok_or_else(|| format!("day {} has no input generator", day.number))?;
+    print!("{input}");
+    Ok(())
+}
+
+/// `aoc lookalike`: prints a synthetic input shaped like a real one, or with `--write`, puts it in
+/// the real one's place and records its answers.
+fn lookalike(day: &Day, args: &[&str]) -> Result<(), Error> {
+    let mut seed = 0;
+    let mut write = false;
+    let mut input = None;
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--seed" => {
+                seed = args
+                    .next()
+                    .ok_or_else(|| usage("missing value for `--seed`"))?
+                    .parse()
+                    .map_err(|_| usage("`--seed` should be a non-negative number"))?;
+            }
+            "--write" => write = true,
+            _ if arg.starts_with("--") => return Err(usage(format!("unknown option `{arg}`"))),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(usage("too many arguments")),
+        }
+    }
+
+    let path = day.input_path(input);
+    if write {
+        let answers = aoc::lookalike::replace(day, &path, seed)?;
+        println!("replaced {}", path.display());
+        for (part, answer) in answers {
+            println!("Day {}, part {part}: {answer}", day.number);
+        }
+        return Ok(());
+    }
+    let input = Source::File(path).read()?;
+    print!("{}", aoc::lookalike::make(day, &input.text, seed)?);
+    Ok(())
+}
+
+/// `aoc watch`: re-runs a day's inputs whenever its sources, inputs or answers change.
+fn watch(day: &Day, args: &[&str]) -> Result<ExitCode, Error> {
+    let mut parts = Part::ALL.to_vec();
+    let mut release = false;
+    let mut timeout = Duration::from_secs(60);
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        match arg {
+            "--release" => release = true,
+            "--timeout" => {
+                timeout = args
+                    .next()
+                    .and_then(|secs| secs.parse().ok())
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| usage("`--timeout` should be a number of seconds"))?;
+            }
+            part if !part.starts_with("--") => parts = vec![parse_part(part)?],
+            _ => return Err(usage(format!("unknown option `{arg}`"))),
+        }
+    }
+
+    aoc::watch::watch(day, &parts, release, timeout)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `aoc serve`: answers `POST /day/{d}/part/{p}` requests on localhost.
+fn serve(args: &[&str]) -> Result<ExitCode, Error> {
+    let mut port = 8023;
+    let mut limits = Limits::default();
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| usage(format!("missing value for `{arg}`")))?;
+        match arg {
+            "--port" => {
+                port = value
+                    .parse()
+                    .map_err(|_| usage("`--port` should be a port number"))?;
+            }
+            "--max-body" => {
+                limits.max_body = value
+                    .parse()
+                    .map_err(|_| usage("`--max-body` should be a number of bytes"))?;
+            }
+            "--timeout" => {
+                limits.timeout = value
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .filter(|timeout| !timeout.is_zero())
+                    .ok_or_else(|| usage("`--timeout` should be a positive number of seconds"))?;
+            }
+            "--max-connections" => {
+                limits.max_connections = value
+                    .parse()
+                    .ok()
+                    .filter(|&max| max > 0)
+                    .ok_or_else(|| usage("`--max-connections` should be a positive number"))?;
+            }
+            _ => return Err(usage(format!("unknown option `{arg}`"))),
+        }
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|err| format!("failed to listen on port {port}: {err}"))?;
+    eprintln!("listening on http://{}", listener.local_addr().unwrap());
+    // Panics are reported in the responses.
+    panic::set_hook(Box::new(|_| {}));
+    aoc::serve::serve(listener, limits)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `aoc all`: every day, part and input on a thread pool, with a summary table.
+fn run_all(args: &[&str]) -> Result<ExitCode, Error> {
+    let mut jobs = thread::available_parallelism().map_or(4, |n| n.get());
+    let mut timeout = Duration::from_secs(60);
+    let mut max_steps = None;
+
+    let mut args = args.iter();
+    while let Some(&arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| usage(format!("missing value for `{arg}`")))?;
+        match arg {
+            "--max-steps" => max_steps = Some(parse_max_steps(value)?),
+            "--jobs" => {
+                jobs = value
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| usage("`--jobs` should be a positive number"))?;
+            }
+            "--timeout" => {
+                timeout = value
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| usage("`--timeout` should be a number of seconds"))?;
+            }
+            _ => return Err(usage(format!("unknown option `{arg}`"))),
+        }
+    }
+
+    // Panics are reported in the table, so the default hook's messages would only be noise.
+    panic::set_hook(Box::new(|_| {}));
+    match aoc::run_all::run_and_summarize(jobs, timeout, max_steps)? {
+        true => Ok(ExitCode::SUCCESS),
+        false => Ok(ExitCode::FAILURE),
+    }
+}
+
+struct BenchOptions {
+    days: Vec<&'static Day>,
+    parts: Vec<Part>,
+    iterations: usize,
+    threshold: f64,
+    baseline: PathBuf,
+    save: bool,
+    /// Compare reading inputs whole against streaming them, instead of timing the steps.
+    stream: bool,
+    input: Option<String>,
+}
+
+impl BenchOptions {
+    fn parse(args: &[&str]) -> Result<BenchOptions, Error> {
+        let mut options = BenchOptions {
+            days: DAYS.iter().collect(),
+            parts: Part::ALL.to_vec(),
+            iterations: 100,
+            threshold: 10.0,
+            baseline: workspace_root().join("bench-baseline.txt"),
+            save: false,
+            stream: false,
+            input: None,
+        };
+
+        let mut positional = Vec::new();
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .copied()
+                    .ok_or_else(|| usage(format!("missing value for `{arg}`")))
+            };
+            match arg {
+                "--iterations" => {
+                    options.iterations = value()?
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| usage("`--iterations` should be a positive number"))?;
+                }
+                "--threshold" => {
+                    options.threshold = value()?
+                        .parse()
+                        .map_err(|_| usage("`--threshold` should be a percentage"))?;
+                }
+                "--baseline" => options.baseline = PathBuf::from(value()?),
+                "--save" => options.save = true,
+                "--stream" => options.stream = true,
+                _ if arg.starts_with("--") => return Err(usage(format!("unknown option `{arg}`"))),
+                _ => positional.push(arg),
+            }
+        }
+
+        // Only streaming takes an input, since baselines are for each day's own.
+        let most = if options.stream { 2 } else { 1 };
+        match positional.as_slice() {
+            [] => {}
+            [day, rest @ ..] if rest.len() <= most => {
+                options.days = vec![parse_day(day)?];
+                if let Some(part) = rest.first() {
+                    options.parts = vec![parse_part(part)?];
+                }
+                options.input = rest.get(1).map(|input| input.to_string());
+            }
+            _ => return Err(usage("too many arguments")),
+        }
+        if options.stream && options.save {
+            return Err(usage("`--save` can't be combined with `--stream`"));
+        }
+        Ok(options)
+    }
+}
+
+fn load_baseline(path: &PathBuf) -> Result<Vec<Measurement>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => {
+            bench::parse_baseline(&text).map_err(|err| format!("{}: {err}", path.display()))
+        }
+        Err(_) if !path.exists() => Ok(Vec::new()),
+        Err(err) => Err(format!("failed to read {}: {err}", path.display())),
+    }
+}
+
+/// `aoc bench --stream`: times each input read whole against streamed. Without a day, only the
+/// days that can stream are compared.
+fn bench_stream(options: &BenchOptions) -> Result<ExitCode, String> {
+    for &day in options
+        .days
+        .iter()
+        .filter(|day| day.streams() || options.days.len() == 1)
+    {
+        let sources = day.sources(options.input.as_deref())?;
+        if sources.contains(&Source::Stdin) {
+            return Err("stdin can't be benchmarked, since it can only be read once".to_string());
+        }
+        for &part in &options.parts {
+            for source in &sources {
+                let comparison = bench::compare_stream(day, part, source, options.iterations)?;
+                match sources.len() {
+                    1 => println!("Day {}, part {part}: {comparison}", day.number),
+                    _ => println!("Day {}, part {part} ({source}): {comparison}", day.number),
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn bench(options: &BenchOptions) -> Result<ExitCode, String> {
+    if options.stream {
+        return bench_stream(options);
+    }
+    let mut baseline = load_baseline(&options.baseline)?;
+    let mut measurements = Vec::new();
+    let mut regressed = 0;
+
+    for &day in &options.days {
+        let [(source, input)] = read_inputs(day, None)?
+            .try_into()
+            .expect("the default input is one file");
+        for &part in &options.parts {
+            let m = bench::measure(day, part, &input, options.iterations)
+                .map_err(|err| format!("failed to parse {source}: {err}"))?;
+            println!(
+                "Day {}, part {part}: parse {} (p95 {}), solve {} (p95 {})",
+                day.number,
+                bench::format_duration(m.parse.median),
+                bench::format_duration(m.parse.p95),
+                bench::format_duration(m.solve.median),
+                bench::format_duration(m.solve.p95),
+            );
+
+            let previous = baseline.iter().find(|b| b.day == m.day && b.part == m.part);
+            for regression in previous
+                .map(|previous| bench::regressions(&m, previous, options.threshold))
+                .unwrap_or_default()
+            {
+                println!("    regression: {regression}");
+                regressed += 1;
+            }
+            measurements.push(m);
+        }
+    }
+
+    if options.save {
+        baseline.retain(|b| {
+            !measurements
+                .iter()
+                .any(|m| (m.day, m.part) == (b.day, b.part))
+        });
+        baseline.extend(&measurements);
+        baseline.sort_by_key(|m| (m.day, m.part));
+        fs::write(&options.baseline, bench::format_baseline(&baseline))
+            .map_err(|err| format!("failed to write {}: {err}", options.baseline.display()))?;
+        println!("saved baseline to {}", options.baseline.display());
+    }
+
+    if regressed > 0 {
+        eprintln!(
+            "{regressed} step(s) slowed down by more than {}% against {}",
+            options.threshold,
+            options.baseline.display()
+        );
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    match success {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, Error> {
+    let mut args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let (format, explain, budget) = match args.first() {
+        Some(&"run") => (
+            take_format(&mut args)?,
+            take_explain(&mut args)?,
+            take_budget(&mut args)?,
+        ),
+        _ => (Format::Text, None, Budget::default()),
+    };
+    let mut take_flag = |flag| match args.iter().position(|&arg| arg == flag) {
+        Some(i) if args.first() == Some(&"run") => {
+            args.remove(i);
+            true
+        }
+        _ => false,
+    };
+    let check = take_flag("--check");
+    let stream = take_flag("--stream");
+    if stream && (check || explain.is_some() || format == Format::Json) {
+        return Err(usage(
+            "`--stream` can't be combined with `--check`, `--explain` or `--format json`",
+        ));
+    }
+    match args.as_slice() {
+        ["run", "--all"] if check => {
+            let mut valid = true;
+            for day in DAYS {
+                valid &= check_day(day, None, None, format)?;
+            }
+            Ok(exit_code(valid))
+        }
+        // The part is optional when checking, so an input may stand in its place.
+        ["run", day, rest @ ..] if check && rest.len() <= 2 => {
+            let part = rest.first().and_then(|arg| arg.parse::<Part>().ok());
+            let input = rest
+                .iter()
+                .copied()
+                .find(|arg| arg.parse::<Part>().is_err());
+            let valid = check_day(parse_day(day)?, part, input_arg(input), format)?;
+            Ok(exit_code(valid))
+        }
+        ["run", "--all"] if stream => Err(usage("`--stream` needs a day")),
+        ["run", day, rest @ ..] if stream && rest.len() <= 2 => {
+            let parts = match rest.first() {
+                Some(part) => vec![parse_part(part)?],
+                None => Part::ALL.to_vec(),
+            };
+            stream_day(
+                parse_day(day)?,
+                &parts,
+                input_arg(rest.get(1).copied()),
+                &budget,
+            )?;
+            Ok(ExitCode::SUCCESS)
+        }
+        ["run", "--all"] => {
+            for day in DAYS {
+                run_day(day, &Part::ALL, None, format, explain, &budget)?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        ["run", day, rest @ ..] if rest.len() <= 2 => {
+            let day = parse_day(day)?;
+            let parts = match rest.first() {
+                Some(part) => vec![parse_part(part)?],
+                None => Part::ALL.to_vec(),
+            };
+            run_day(
+                day,
+                &parts,
+                input_arg(rest.get(1).copied()),
+                format,
+                explain,
+                &budget,
+            )?;
+            Ok(ExitCode::SUCCESS)
+        }
+        ["alloc"] => {
+            count_allocations(&DAYS.iter().collect::<Vec<_>>(), &Part::ALL, None)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        ["alloc", day, rest @ ..] if rest.len() <= 2 => {
+            let parts = match rest.first() {
+                Some(part) => vec![parse_part(part)?],
+                None => Part::ALL.to_vec(),
+            };
+            count_allocations(&[parse_day(day)?], &parts, input_arg(rest.get(1).copied()))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        ["new", day] => new_day(day).map(|()| ExitCode::SUCCESS),
+        ["fetch", day] => fetch(day).map(|()| ExitCode::SUCCESS),
+        ["submit", day, part, rest @ ..] if rest.len() <= 1 => Ok(submit(
+            parse_day(day)?,
+            parse_part(part)?,
+            input_arg(rest.first().copied()),
+        )?),
+        ["gen", day, rest @ ..] => generate(parse_day(day)?, rest).map(|()| ExitCode::SUCCESS),
+        ["lookalike", day, rest @ ..] => {
+            lookalike(parse_day(day)?, rest).map(|()| ExitCode::SUCCESS)
+        }
+        ["all", rest @ ..] => run_all(rest),
+        ["watch", day, rest @ ..] => watch(parse_day(day)?, rest),
+        ["serve", rest @ ..] => serve(rest),
+        ["bench", rest @ ..] => Ok(bench(&BenchOptions::parse(rest)?)?),
+        _ => Err(usage("invalid arguments")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(code) => code,
+        Err(Error::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+        Err(Error::Failed(message)) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! `aoc all`: runs every day, part and input file in parallel and summarizes the results in a
+//! table, checked against the days' `answers.txt` manifests.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use common::{
+    budget::{self, panic_message, Budget, Cancel, Exceeded},
+    input::Source,
+    Answer, Part, Timings,
+};
+
+use crate::{bench::format_duration, Day, DAYS};
+
+/// A job gets the flag that's set if it runs out of time, to run its solution with (see
+/// [`budget`]).
+type Job<T> = Box<dyn FnOnce(Cancel) -> T + Send>;
+
+/// Runs `jobs` on `threads` worker threads, returning their results in the same order. A job
+/// that takes longer than `timeout` gets `None` and is cancelled. It's left to stop at its next
+/// budget check in the background rather than holding up its worker, so one slow job can't block
+/// the others.
+pub fn run_parallel<T: Send + 'static>(
+    jobs: Vec<Job<T>>,
+    threads: usize,
+    timeout: Duration,
+) -> Vec<Option<T>> {
+    let count = jobs.len();
+    let queue = Arc::new(Mutex::new(
+        jobs.into_iter().enumerate().collect::<VecDeque<_>>(),
+    ));
+    let (results_tx, results_rx) = mpsc::channel();
+
+    for _ in 0..threads.clamp(1, count.max(1)) {
+        let queue = Arc::clone(&queue);
+        let results_tx = results_tx.clone();
+        thread::spawn(move || loop {
+            let Some((i, job)) = queue.lock().unwrap().pop_front() else {
+                break;
+            };
+            let (tx, rx) = mpsc::channel();
+            let cancel = Cancel::new();
+            let job_cancel = cancel.clone();
+            thread::spawn(move || {
+                let _ = tx.send(job(job_cancel));
+            });
+            let result = rx.recv_timeout(timeout).ok();
+            if result.is_none() {
+                cancel.cancel();
+            }
+            let _ = results_tx.send((i, result));
+        });
+    }
+    drop(results_tx);
+
+    let mut results = (0..count).map(|_| None).collect::<Vec<_>>();
+    for (i, result) in results_rx {
+        results[i] = result;
+    }
+    results
+}
+
+/// How one run went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answered(Answer, Timings),
+    /// The input couldn't be read or parsed.
+    Failed(String),
+    Panicked(String),
+    TimedOut,
+    /// The solution took more steps than it was allowed.
+    Stopped(Exceeded),
+}
+
+/// How an outcome compares with the recorded answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// There's no recorded answer to compare with.
+    Unchecked,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Unchecked => write!(f, "-"),
+            Status::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub day: u8,
+    pub part: Part,
+    /// The input's file name within the day's `inputs/` folder.
+    pub file: String,
+    pub outcome: Outcome,
+    pub expected: Option<Answer>,
+}
+
+impl Run {
+    pub fn status(&self) -> Status {
+        match (&self.outcome, self.expected) {
+            (Outcome::Answered(answer, _), Some(expected)) if *answer == expected => Status::Pass,
+            (Outcome::Answered(..), Some(_)) => Status::Fail,
+            (Outcome::Answered(..), None) => Status::Unchecked,
+            _ => Status::Error,
+        }
+    }
+}
+
+fn run_one(day: &'static Day, part: Part, source: &Source, budget: &Budget) -> Outcome {
+    let input = match source.read() {
+        Ok(input) => input.text.into_owned(),
+        Err(message) => return Outcome::Failed(message),
+    };
+    let run = || budget::run(budget, || day.solve_timed(part, &input));
+    match panic::catch_unwind(AssertUnwindSafe(run)) {
+        Ok(Ok(Ok((answer, timings)))) => Outcome::Answered(answer, timings),
+        Ok(Ok(Err(err))) => Outcome::Failed(format!(
+            "parse error at line {}, column {}: expected {}",
+            err.line, err.column, err.expected
+        )),
+        // Only a step limit can stop it before `run_parallel` gives up on it.
+        Ok(Err(exceeded)) => Outcome::Stopped(exceeded),
+        Err(payload) => Outcome::Panicked(panic_message(payload)),
+    }
+}
+
+/// Runs every day against every `*.txt` file in its `inputs/` folder, each within `timeout` and,
+/// if it's given, `max_steps` (see [`budget`]). Inputs in `answers.txt` are only run for the
+/// parts recorded there, since some are only meant for one part, and the rest for both. Panics
+/// are caught and reported, but the panic hook still runs, so callers may want to silence it.
+pub fn run_all(
+    threads: usize,
+    timeout: Duration,
+    max_steps: Option<u64>,
+) -> Result<Vec<Run>, String> {
+    let mut runs = Vec::new();
+    let mut jobs: Vec<Job<Outcome>> = Vec::new();
+
+    for day in DAYS {
+        let expected = day.expected_answers()?;
+        for source in Source::expand(&day.dir().join("inputs"))? {
+            let Source::File(path) = &source else {
+                unreachable!("directories only expand to files");
+            };
+            let file = path.file_name().unwrap().to_string_lossy().into_owned();
+            let mut parts = Part::ALL
+                .into_iter()
+                .filter(|&part| expected.iter().any(|e| e.file == file && e.part == part))
+                .collect::<Vec<_>>();
+            if parts.is_empty() {
+                parts = Part::ALL.to_vec();
+            }
+            for part in parts {
+                let source = source.clone();
+                jobs.push(Box::new(move |cancel| {
+                    let budget = Budget {
+                        steps: max_steps,
+                        cancel: Some(cancel),
+                        ..Budget::default()
+                    };
+                    run_one(day, part, &source, &budget)
+                }));
+                runs.push(Run {
+                    day: day.number,
+                    part,
+                    expected: expected
+                        .iter()
+                        .find(|e| e.file == file && e.part == part)
+                        .map(|e| e.answer),
+                    file: file.clone(),
+                    outcome: Outcome::TimedOut,
+                });
+            }
+        }
+    }
+
+    for (run, outcome) in runs.iter_mut().zip(run_parallel(jobs, threads, timeout)) {
+        run.outcome = outcome.unwrap_or(Outcome::TimedOut);
+    }
+    Ok(runs)
+}
+
+/// Lines up `rows` under `header` in columns two spaces apart. The columns in `right_aligned`,
+/// usually numbers, line up on the right and the rest on the left.
+pub fn format_table<const N: usize>(
+    header: &[&str; N],
+    rows: &[[String; N]],
+    right_aligned: &[usize],
+) -> String {
+    let header = header.map(String::from);
+    let mut widths = header.clone().map(|cell| cell.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(rows) {
+        let cells = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| match right_aligned.contains(&i) {
+                true => format!("{cell:>width$}"),
+                false => format!("{cell:<width$}"),
+            })
+            .collect::<Vec<_>>();
+        table += cells.join("  ").trim_end();
+        table.push('\n');
+    }
+    table
+}
+
+/// Lays out the runs as a table, followed by totals.
+pub fn summarize(runs: &[Run], wall_time: Duration) -> String {
+    let header = ["day", "part", "input", "answer", "parse", "solve", "status"];
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let (answer, parse, solve) = match &run.outcome {
+                Outcome::Answered(answer, timings) => (
+                    answer.to_string(),
+                    format_duration(timings.parse),
+                    format_duration(timings.solve),
+                ),
+                Outcome::Failed(message) => (message.clone(), String::new(), String::new()),
+                Outcome::Panicked(message) => {
+                    (format!("panicked: {message}"), String::new(), String::new())
+                }
+                Outcome::TimedOut => ("timed out".to_string(), String::new(), String::new()),
+                Outcome::Stopped(exceeded) => (exceeded.to_string(), String::new(), String::new()),
+            };
+            let status = match (run.status(), run.expected) {
+                (Status::Fail, Some(expected)) => format!("FAIL (expected {expected})"),
+                (status, _) => status.to_string(),
+            };
+            [
+                run.day.to_string(),
+                run.part.to_string(),
+                run.file.clone(),
+                answer,
+                parse,
+                solve,
+                status,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut table = format_table(&header, &rows, &[0, 1, 4, 5]);
+
+    let count = |status| runs.iter().filter(|run| run.status() == status).count();
+    let total_time = runs
+        .iter()
+        .filter_map(|run| match run.outcome {
+            Outcome::Answered(_, timings) => Some(timings.parse + timings.solve),
+            _ => None,
+        })
+        .sum();
+    table += &format!(
+        "\n{} runs: {} passed, {} failed, {} errors, {} unchecked; {} solving, {} wall time\n",
+        runs.len(),
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Error),
+        count(Status::Unchecked),
+        format_duration(total_time),
+        format_duration(wall_time),
+    );
+    table
+}
+
+/// Runs everything, printing the summary, and reports whether every run passed or was unchecked.
+/// Runs that fail or panic count against it whether or not there's an answer to compare with.
+pub fn run_and_summarize(
+    threads: usize,
+    timeout: Duration,
+    max_steps: Option<u64>,
+) -> Result<bool, String> {
+    let start = Instant::now();
+    let runs = run_all(threads, timeout, max_steps)?;
+    print!("{}", summarize(&runs, start.elapsed()));
+    Ok(runs
+        .iter()
+        .all(|run| matches!(run.status(), Status::Pass | Status::Unchecked)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_parallel_keeps_order() {
+        let jobs = (0..20u64)
+            .map(|i| -> Job<u64> {
+                Box::new(move |_| {
+                    thread::sleep(Duration::from_millis(20 - i));
+                    i * i
+                })
+            })
+            .collect();
+        let results = run_parallel(jobs, 4, Duration::from_secs(10));
+        assert_eq!(results, (0..20).map(|i| Some(i * i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_run_parallel_times_out_without_blocking() {
+        let mut jobs: Vec<Job<u32>> = vec![Box::new(|_| {
+            thread::sleep(Duration::from_secs(60));
+            0
+        })];
+        jobs.extend((1..=10).map(|i| -> Job<u32> { Box::new(move |_| i) }));
+
+        let start = Instant::now();
+        let results = run_parallel(jobs, 2, Duration::from_millis(200));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(results[0], None);
+        assert_eq!(results[1..], (1..=10).map(Some).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_run_parallel_cancels_timed_out_jobs() {
+        let (stopped_tx, stopped_rx) = mpsc::channel();
+        let job: Job<()> = Box::new(move |cancel| {
+            let budget = Budget {
+                cancel: Some(cancel),
+                ..Budget::default()
+            };
+            let _ = stopped_tx.send(budget::run(&budget, || loop {
+                budget::step();
+            }));
+        });
+
+        assert_eq!(
+            run_parallel(vec![job], 1, Duration::from_millis(50)),
+            [None]
+        );
+        assert_eq!(
+            stopped_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(Err(Exceeded::Cancelled))
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        let timings = Timings {
+            parse: Duration::from_micros(5),
+            solve: Duration::from_millis(2),
+        };
+        let run = |file: &str, outcome, expected: Option<i32>| Run {
+            day: 6,
+            part: Part::One,
+            file: file.to_string(),
+            outcome,
+            expected: expected.map(Answer::from),
+        };
+        let runs = [
+            run(
+                "example1.txt",
+                Outcome::Answered(288.into(), timings),
+                Some(288),
+            ),
+            run("input.txt", Outcome::Answered(7.into(), timings), Some(8)),
+            run("extra.txt", Outcome::Answered(1.into(), timings), None),
+            run("slow.txt", Outcome::TimedOut, None),
+            run("loop.txt", Outcome::Stopped(Exceeded::Steps(1000)), None),
+            run("crash.txt", Outcome::Panicked("boom".to_string()), None),
+        ];
+
+        assert_eq!(
+            summarize(&runs, Duration::from_millis(3)),
+            "\
+day  part  input         answer                                parse   solve  status
+  6     1  example1.txt  288                                  5.00µs  2.00ms  pass
+  6     1  input.txt     7                                    5.00µs  2.00ms  FAIL (expected 8)
+  6     1  extra.txt     1                                    5.00µs  2.00ms  -
+  6     1  slow.txt      timed out                                            ERROR
+  6     1  loop.txt      did not terminate within 1000 steps                  ERROR
+  6     1  crash.txt     panicked: boom                                       ERROR
+
+6 runs: 1 passed, 1 failed, 3 errors, 1 unchecked; 6.01ms solving, 3.00ms wall time
+"
+        );
+        assert_eq!(runs[5].status(), Status::Error);
+    }
+}
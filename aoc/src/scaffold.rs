@@ -0,0 +1,197 @@
+//! `aoc new <day>`: creates a day crate from the files in `aoc/template/` and registers it in the
+//! workspace members, the `aoc` crate's dependencies and the `days!` list.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const CARGO_TOML: &str = include_str!("../template/Cargo.toml.tmpl");
+const LIB_RS: &str = include_str!("../template/lib.rs.tmpl");
+const PART_RS: &str = include_str!("../template/part.rs.tmpl");
+const ANSWERS_TXT: &str = include_str!("../template/answers.txt.tmpl");
+
+fn render(template: &str, number: u8, part: &str) -> String {
+    template
+        .replace("{{crate}}", &format!("day{number:02}"))
+        .replace("{{type}}", &format!("Day{number:02}"))
+        .replace("{{part}}", part)
+}
+
+/// Creates `day<NN>` under the workspace at `root` and registers it, returning every file
+/// created or changed.
+pub fn new_day(root: &Path, number: u8) -> Result<Vec<PathBuf>, String> {
+    let name = format!("day{number:02}");
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    // Work out every edit before writing anything, so a failure leaves the workspace untouched.
+    let edits = [
+        (
+            root.join("Cargo.toml"),
+            add_workspace_member as fn(&str, &str) -> _,
+        ),
+        (root.join("aoc/Cargo.toml"), add_dependency),
+        (root.join("aoc/src/lib.rs"), add_to_days),
+    ]
+    .into_iter()
+    .map(|(path, edit)| {
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let text = edit(&text, &name).map_err(|err| format!("{}: {err}", path.display()))?;
+        Ok((path, text))
+    })
+    .collect::<Result<Vec<_>, String>>()?;
+
+    let files = [
+        ("Cargo.toml", render(CARGO_TOML, number, "")),
+        ("answers.txt", render(ANSWERS_TXT, number, "")),
+        ("src/lib.rs", render(LIB_RS, number, "")),
+        ("src/bin/part1.rs", render(PART_RS, number, "One")),
+        ("src/bin/part2.rs", render(PART_RS, number, "Two")),
+        ("inputs/example1.txt", String::new()),
+    ]
+    .map(|(path, text)| (dir.join(path), text));
+
+    let mut written = Vec::new();
+    for (path, text) in files.into_iter().chain(edits) {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+        }
+        fs::write(&path, text)
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Adds `"dayNN",` to the workspace `members` list, keeping it sorted.
+fn add_workspace_member(cargo_toml: &str, name: &str) -> Result<String, String> {
+    let start = cargo_toml
+        .find("members = [\n")
+        .ok_or("no multi-line `members` list")?
+        + "members = [\n".len();
+    let end = start
+        + cargo_toml[start..]
+            .find(']')
+            .ok_or("unterminated `members` list")?;
+
+    let mut members = cargo_toml[start..end].lines().collect::<Vec<_>>();
+    let member = format!("    \"{name}\",");
+    insert_sorted(&mut members, &member)?;
+    Ok(format!(
+        "{}{}\n{}",
+        &cargo_toml[..start],
+        members.join("\n"),
+        &cargo_toml[end..]
+    ))
+}
+
+/// Adds `dayNN = { path = "../dayNN" }` next to the other day dependencies.
+fn add_dependency(cargo_toml: &str, name: &str) -> Result<String, String> {
+    let mut lines = cargo_toml.lines().collect::<Vec<_>>();
+    let days = lines
+        .iter()
+        .position(|line| is_day_line(line))
+        .ok_or("no day dependencies to add to")?;
+    let count = lines[days..]
+        .iter()
+        .take_while(|line| is_day_line(line))
+        .count();
+
+    let dependency = format!("{name} = {{ path = \"../{name}\" }}");
+    let mut deps = lines[days..days + count].to_vec();
+    insert_sorted(&mut deps, &dependency)?;
+    lines.splice(days..days + count, deps);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `N => dayNN::DayNN,` to the `days!` list.
+fn add_to_days(lib_rs: &str, name: &str) -> Result<String, String> {
+    let mut lines = lib_rs.lines().collect::<Vec<_>>();
+    let start = lines
+        .iter()
+        .position(|line| *line == "days! {")
+        .ok_or("no `days!` list")?
+        + 1;
+    let count = lines[start..]
+        .iter()
+        .take_while(|line| *line != &"}")
+        .count();
+
+    let number: u8 = name["day".len()..].parse().map_err(|_| "invalid day")?;
+    let entry = format!("    {number} => {name}::Day{number:02},");
+    let mut days = lines[start..start + count].to_vec();
+    if days.iter().any(|line| line.contains(&format!("{name}::"))) {
+        return Err(format!("{name} is already listed"));
+    }
+    days.push(&entry);
+    days.sort_by_key(|line| {
+        line.trim()
+            .split(' ')
+            .next()
+            .and_then(|number| number.parse::<u8>().ok())
+    });
+    lines.splice(start..start + count, days);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn is_day_line(line: &str) -> bool {
+    line.strip_prefix("day")
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+fn insert_sorted<'a>(lines: &mut Vec<&'a str>, line: &'a str) -> Result<(), String> {
+    match lines.binary_search(&line) {
+        Ok(_) => Err(format!("`{}` is already listed", line.trim())),
+        Err(i) => {
+            lines.insert(i, line);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_workspace_member() {
+        let cargo_toml = "[workspace]\nmembers = [\n    \"aoc\",\n    \"day01\",\n    \"day11\",\n    \"grid\",\n]\n\n[profile.release]\n";
+        assert_eq!(
+            add_workspace_member(cargo_toml, "day02"),
+            Ok("[workspace]\nmembers = [\n    \"aoc\",\n    \"day01\",\n    \"day02\",\n    \"day11\",\n    \"grid\",\n]\n\n[profile.release]\n".to_string())
+        );
+        assert!(add_workspace_member(cargo_toml, "day11").is_err());
+    }
+
+    #[test]
+    fn test_add_dependency() {
+        let cargo_toml = "[dependencies]\ncommon = { path = \"../common\" }\nday01 = { path = \"../day01\" }\nday11 = { path = \"../day11\" }\nureq = \"2.9\"\n";
+        assert_eq!(
+            add_dependency(cargo_toml, "day12"),
+            Ok("[dependencies]\ncommon = { path = \"../common\" }\nday01 = { path = \"../day01\" }\nday11 = { path = \"../day11\" }\nday12 = { path = \"../day12\" }\nureq = \"2.9\"\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_add_to_days() {
+        let lib_rs = "days! {\n    1 => day01::Day01,\n    10 => day10::Day10,\n}\n";
+        assert_eq!(
+            add_to_days(lib_rs, "day09"),
+            Ok("days! {\n    1 => day01::Day01,\n    9 => day09::Day09,\n    10 => day10::Day10,\n}\n".to_string())
+        );
+        assert!(add_to_days(lib_rs, "day10").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render("use {{crate}}::{{type}}; Part::{{part}}", 12, "Two"),
+            "use day12::Day12; Part::Two"
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! `aoc serve`: a small HTTP server for running solvers without shelling out.
+//!
+//! `POST /day/{d}/part/{p}` with the puzzle text as the body solves that part and responds with
+//! JSON: the answer and timings on success, or the parse error with a `422` status. Anything that
+//! goes wrong before solving gets an `{"error": ...}` object with a fitting status.
+//!
+//! Only so many requests are handled at once (see [`Limits::max_connections`]), and the rest wait
+//! to be accepted. A solve that times out is cancelled through its budget, which only stops
+//! solvers that call [`budget::step`] in their long loops. One that doesn't keeps running in the
+//! background and keeps its request's slot taken until it's done, so runaway solves can't pile
+//! up.
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use common::{
+    budget::{self, panic_message, Budget},
+    Part, Value,
+};
+
+use crate::{run_all::run_parallel, Day};
+
+pub const DEFAULT_MAX_BODY: usize = 1 << 20;
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_CONNECTIONS: usize = 8;
+
+/// Longest request line and headers that are read before giving up on a request.
+const MAX_HEADER: usize = 8 << 10;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Largest body accepted, in bytes.
+    pub max_body: usize,
+    /// How long a request may take, both to be sent and to be solved.
+    pub timeout: Duration,
+    /// Most requests handled at once, counting solves that timed out but haven't stopped yet.
+    pub max_connections: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_body: DEFAULT_MAX_BODY,
+            timeout: DEFAULT_TIMEOUT,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        }
+    }
+}
+
+/// A fixed number of slots for requests to be handled in.
+struct Slots {
+    free: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl Slots {
+    fn new(count: usize) -> Arc<Slots> {
+        Arc::new(Slots {
+            free: Mutex::new(count),
+            freed: Condvar::new(),
+        })
+    }
+
+    /// Takes a slot, waiting for one to be given back if they're all taken.
+    fn take(self: &Arc<Self>) -> Slot {
+        let mut free = self.free.lock().unwrap();
+        while *free == 0 {
+            free = self.freed.wait(free).unwrap();
+        }
+        *free -= 1;
+        Slot(Arc::clone(self))
+    }
+}
+
+/// A taken slot, which is given back when it's dropped.
+pub struct Slot(Arc<Slots>);
+
+impl Slot {
+    /// A slot of its own, for handling a request outside of [`serve`].
+    pub fn single() -> Slot {
+        Slots::new(1).take()
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        *self.0.free.lock().unwrap() += 1;
+        self.0.freed.notify_one();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response {
+            status,
+            body: Value::Object(vec![("error", message.into().into())]),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Content Too Large",
+        422 => "Unprocessable Content",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+fn read_error(err: io::Error) -> Response {
+    match err.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            Response::error(408, "timed out reading the request")
+        }
+        _ => Response::error(400, format!("failed to read the request: {err}")),
+    }
+}
+
+/// A stream whose reads can be given a timeout.
+pub trait TimedRead: Read {
+    fn set_read_timeout(&mut self, timeout: Duration) -> io::Result<()>;
+}
+
+impl TimedRead for &TcpStream {
+    fn set_read_timeout(&mut self, timeout: Duration) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, Some(timeout))
+    }
+}
+
+/// Bytes already in memory, which never keep a read waiting.
+impl TimedRead for &[u8] {
+    fn set_read_timeout(&mut self, _timeout: Duration) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads from a stream until `deadline`, giving each read only the time that's left, so a
+/// request sent a little at a time can't take longer than the limit in total.
+struct Deadline<R> {
+    stream: R,
+    deadline: Instant,
+}
+
+impl<R: TimedRead> Read for Deadline<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(left)?;
+        self.stream.read(buf)
+    }
+}
+
+/// Reads one request, turning anything malformed, over the limits or not sent in full within
+/// the timeout into the response to send instead.
+pub fn read_request(stream: impl TimedRead, limits: Limits) -> Result<Request, Response> {
+    let mut reader = BufReader::new(Deadline {
+        stream,
+        deadline: Instant::now() + limits.timeout,
+    });
+    let mut head = Vec::new();
+    loop {
+        let start = head.len();
+        let read = (&mut reader)
+            .take((MAX_HEADER + 1 - start) as u64)
+            .read_until(b'\n', &mut head)
+            .map_err(read_error)?;
+        if head.len() > MAX_HEADER {
+            return Err(Response::error(
+                431,
+                "request line and headers are too long",
+            ));
+        }
+        if read == 0 {
+            return Err(Response::error(400, "request ended before its headers did"));
+        }
+        if head[start..] == *b"\r\n" || head[start..] == *b"\n" {
+            break;
+        }
+    }
+    let head = String::from_utf8(head)
+        .map_err(|_| Response::error(400, "request headers aren't valid UTF-8"))?;
+
+    let mut lines = head.lines();
+    let mut words = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (words.next(), words.next()) else {
+        return Err(Response::error(400, "malformed request line"));
+    };
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("Content-Length"))
+        .map(|(_, value)| value.trim().parse::<usize>());
+
+    let length = match (method, content_length) {
+        (_, Some(Ok(length))) => length,
+        (_, Some(Err(_))) => return Err(Response::error(400, "invalid Content-Length")),
+        ("POST", None) => return Err(Response::error(411, "Content-Length is required")),
+        (_, None) => 0,
+    };
+    if length > limits.max_body {
+        return Err(Response::error(
+            413,
+            format!("body is over the limit of {} bytes", limits.max_body),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(read_error)?;
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: String::from_utf8(body)
+            .map_err(|_| Response::error(400, "body isn't valid UTF-8"))?,
+    })
+}
+
+/// Solves one part of `day` on another thread, so that it can be abandoned and cancelled after
+/// `timeout`. The solve holds on to `slot` until it stops, however long after that it is.
+fn solve(day: &'static Day, part: Part, input: String, timeout: Duration, slot: Slot) -> Response {
+    let job = Box::new(move |cancel| {
+        let _slot = slot;
+        let budget = Budget {
+            cancel: Some(cancel),
+            ..Budget::default()
+        };
+        let run = || budget::run(&budget, || day.solve_timed(part, &input));
+        panic::catch_unwind(AssertUnwindSafe(run)).map_err(panic_message)
+    });
+    let mut fields = vec![("day", day.number.into()), ("part", part.number().into())];
+    match run_parallel(vec![job], 1, timeout).pop().flatten() {
+        Some(Ok(Ok(Ok((answer, timings))))) => {
+            fields.push(("answer", answer.value().into()));
+            fields.push((
+                "timings",
+                Value::Object(vec![
+                    ("parse_ns", (timings.parse.as_nanos() as u64).into()),
+                    ("solve_ns", (timings.solve.as_nanos() as u64).into()),
+                ]),
+            ));
+            Response {
+                status: 200,
+                body: Value::Object(fields),
+            }
+        }
+        Some(Ok(Ok(Err(err)))) => {
+            fields.push((
+                "error",
+                Value::Object(vec![
+                    ("message", err.to_string().into()),
+                    ("line", err.line.into()),
+                    ("column", err.column.into()),
+                    ("expected", err.expected.into()),
+                ]),
+            ));
+            Response {
+                status: 422,
+                body: Value::Object(fields),
+            }
+        }
+        Some(Err(message)) => Response::error(500, format!("panicked: {message}")),
+        // Jobs are only cancelled once they've timed out.
+        Some(Ok(Err(_))) | None => Response::error(
+            503,
+            format!("timed out after {:.1}s", timeout.as_secs_f64()),
+        ),
+    }
+}
+
+/// Answers a request that's been read in full, keeping `slot` taken while solving it.
+pub fn route(request: Request, limits: Limits, slot: Slot) -> Response {
+    let segments = request.path.split('/').skip(1).collect::<Vec<_>>();
+    let ["day", day, "part", part] = segments.as_slice() else {
+        return Response::error(404, format!("no such path `{}`", request.path));
+    };
+    let Some(day) = day.parse().ok().and_then(Day::get) else {
+        return Response::error(404, format!("unknown day `{day}`"));
+    };
+    let part = match part.parse() {
+        Ok(part) => part,
+        Err(message) => return Response::error(404, message),
+    };
+    if request.method != "POST" {
+        return Response::error(405, "only POST is supported");
+    }
+    solve(day, part, request.body, limits.timeout, slot)
+}
+
+fn handle(mut stream: TcpStream, limits: Limits, slot: Slot) {
+    let start = Instant::now();
+    let (summary, response) = match read_request(&stream, limits) {
+        Ok(request) => (
+            format!("{} {}", request.method, request.path),
+            route(request, limits, slot),
+        ),
+        Err(response) => ("-".to_string(), response),
+    };
+
+    let body = response.body.to_json();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        response.status,
+        reason(response.status),
+        body.len()
+    );
+    eprintln!("{summary} {} ({:.1?})", response.status, start.elapsed());
+}
+
+/// Serves requests on `listener` until it fails, each on its own thread, up to
+/// `limits.max_connections` at once.
+pub fn serve(listener: TcpListener, limits: Limits) -> Result<(), String> {
+    let slots = Slots::new(limits.max_connections);
+    loop {
+        // Connections wait to be accepted until there's a slot for them.
+        let slot = slots.take();
+        let (stream, _) = listener
+            .accept()
+            .map_err(|err| format!("failed to accept a connection: {err}"))?;
+        thread::spawn(move || handle(stream, limits, slot));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(request: &str, max_body: usize) -> Result<Request, Response> {
+        let limits = Limits {
+            max_body,
+            ..Limits::default()
+        };
+        read_request(request.as_bytes(), limits)
+    }
+
+    #[test]
+    fn test_read_request() {
+        assert_eq!(
+            read(
+                "POST /day/6/part/1 HTTP/1.1\r\nHost: x\r\ncontent-length: 5\r\n\r\nhello",
+                10
+            ),
+            Ok(Request {
+                method: "POST".to_string(),
+                path: "/day/6/part/1".to_string(),
+                body: "hello".to_string(),
+            })
+        );
+        let status = |request, max_body| read(request, max_body).unwrap_err().status;
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: 11\r\n\r\n", 10),
+            413
+        );
+        assert_eq!(status("POST / HTTP/1.1\r\n\r\n", 10), 411);
+        assert_eq!(
+            status("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhi", 10),
+            400
+        );
+        assert_eq!(status("POST / HTTP/1.1\r\nHost: x", 10), 400);
+        let long = format!("GET / HTTP/1.1\r\nX: {}\r\n\r\n", "a".repeat(MAX_HEADER));
+        assert_eq!(status(&long, 10), 431);
+    }
+
+    /// A request that arrives a byte at a time, each after `delay`.
+    struct Trickle {
+        bytes: &'static [u8],
+        delay: Duration,
+    }
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            thread::sleep(self.delay);
+            (&mut self.bytes).take(1).read(buf)
+        }
+    }
+
+    impl TimedRead for Trickle {
+        fn set_read_timeout(&mut self, timeout: Duration) -> io::Result<()> {
+            match timeout < self.delay {
+                true => Err(io::ErrorKind::WouldBlock.into()),
+                false => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_request_times_out_in_total() {
+        let limits = Limits {
+            timeout: Duration::from_millis(200),
+            ..Limits::default()
+        };
+        let request = b"POST /day/6/part/1 HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello";
+        let start = Instant::now();
+        let trickle = Trickle {
+            bytes: request,
+            delay: Duration::from_millis(10),
+        };
+        assert_eq!(read_request(trickle, limits).unwrap_err().status, 408);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_route() {
+        let post = |path: &str, body: &str| {
+            route(
+                Request {
+                    method: "POST".to_string(),
+                    path: path.to_string(),
+                    body: body.to_string(),
+                },
+                Limits::default(),
+                Slot::single(),
+            )
+        };
+        let response = post("/day/6/part/1", "Time: 7\nDistance: 9");
+        assert_eq!(response.status, 200);
+        assert!(response
+            .body
+            .to_json()
+            .starts_with(r#"{"day":6,"part":1,"answer":4,"timings":{"parse_ns":"#));
+
+        let response = post("/day/6/part/1", "Time: 7\nDistance 9");
+        assert_eq!(response.status, 422);
+        assert!(response
+            .body
+            .to_json()
+            .contains(r#""line":1,"column":8,"expected":"`Distance:` on the next line""#));
+
+        assert_eq!(post("/day/99/part/1", "").status, 404);
+        assert_eq!(post("/day/6/part/3", "").status, 404);
+        assert_eq!(post("/days", "").status, 404);
+        let get = Request {
+            method: "GET".to_string(),
+            path: "/day/6/part/1".to_string(),
+            body: String::new(),
+        };
+        assert_eq!(route(get, Limits::default(), Slot::single()).status, 405);
+    }
+}
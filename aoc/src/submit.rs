@@ -0,0 +1,369 @@
+//! Submitting answers, for `aoc submit`. Every guess is recorded in `guesses.txt` in the state
+//! directory, one per line:
+//!
+//! ```text
+//! # year day part answer verdict
+//! 2023 10 2 512 too-high
+//! 2023 10 2 483 right
+//! ```
+//!
+//! Before posting, the history is checked so that an answer that's already been rejected, or that
+//! an earlier "too high" or "too low" reply rules out, is never sent again.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use common::{Answer, Part};
+
+use crate::client::Client;
+
+/// The site's reply to a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after a previous answer; this is how long is left to wait.
+    Wait(Duration),
+    /// The part isn't the one being solved, usually because it's already been completed.
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the HTML page the site replies with.
+    pub fn from_response(html: &str) -> Option<Verdict> {
+        if html.contains("That's the right answer") {
+            Some(Verdict::Right)
+        } else if html.contains("You gave an answer too recently") {
+            Some(Verdict::Wait(parse_wait(html).unwrap_or_default()))
+        } else if html.contains("You don't seem to be solving the right level") {
+            Some(Verdict::WrongLevel)
+        } else if html.contains("That's not the right answer") {
+            Some(if html.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if html.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Whether the site ruled the answer out, as opposed to not judging it at all.
+    pub fn is_rejection(self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+/// Finds the `1m 23s` in "You have 1m 23s left to wait".
+fn parse_wait(html: &str) -> Option<Duration> {
+    let end = html.find(" left to wait")?;
+    let start = html[..end].rfind("have ")? + "have ".len();
+    html[start..end]
+        .split_whitespace()
+        .map(|amount| {
+            let (number, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+            let number: u64 = number.parse().ok()?;
+            match unit {
+                "h" => Some(number * 3600),
+                "m" => Some(number * 60),
+                "s" => Some(number),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Wait(wait) => write!(f, "wait-{}s", wait.as_secs()),
+            Verdict::WrongLevel => write!(f, "wrong-level"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Verdict::Right),
+            "wrong" => Ok(Verdict::Wrong),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wrong-level" => Ok(Verdict::WrongLevel),
+            _ => s
+                .strip_prefix("wait-")
+                .and_then(|wait| wait.strip_suffix('s'))
+                .and_then(|secs| secs.parse().ok())
+                .map(|secs| Verdict::Wait(Duration::from_secs(secs)))
+                .ok_or_else(|| format!("invalid verdict `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guess {
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+/// Every answer submitted so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    pub guesses: Vec<Guess>,
+}
+
+impl History {
+    pub fn parse(text: &str) -> Result<History, String> {
+        let guesses = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(i, line)| parse_guess(line).map_err(|err| format!("line {}: {err}", i + 1)))
+            .collect::<Result<_, _>>()?;
+        Ok(History { guesses })
+    }
+
+    pub fn load(path: &Path) -> Result<History, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => History::parse(&text).map_err(|err| format!("{}: {err}", path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(format!("failed to read {}: {err}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut text = "# year day part answer verdict\n".to_string();
+        for guess in &self.guesses {
+            text += &format!(
+                "{} {} {} {} {}\n",
+                guess.year, guess.day, guess.part, guess.answer, guess.verdict
+            );
+        }
+        fs::write(path, text).map_err(|err| format!("failed to write {}: {err}", path.display()))
+    }
+
+    /// Explains why `answer` shouldn't be submitted, if the history already settles it.
+    pub fn check(&self, year: u16, day: u8, part: Part, answer: Answer) -> Result<(), String> {
+        let guesses = self
+            .guesses
+            .iter()
+            .filter(|guess| (guess.year, guess.day, guess.part) == (year, day, part));
+
+        for guess in guesses {
+            match guess.verdict {
+                Verdict::Right if guess.answer == answer => {
+                    return Err(format!("{answer} was already accepted"));
+                }
+                Verdict::Right => {
+                    return Err(format!(
+                        "this part was already solved with {}",
+                        guess.answer
+                    ));
+                }
+                verdict if verdict.is_rejection() && guess.answer == answer => {
+                    return Err(format!("{answer} was already rejected as {verdict}"));
+                }
+                Verdict::TooHigh if answer > guess.answer => {
+                    return Err(format!(
+                        "{answer} is higher than {}, which was too high",
+                        guess.answer
+                    ));
+                }
+                Verdict::TooLow if answer < guess.answer => {
+                    return Err(format!(
+                        "{answer} is lower than {}, which was too low",
+                        guess.answer
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_guess(line: &str) -> Result<Guess, String> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let [year, day, part, answer, verdict] = fields.as_slice() else {
+        return Err(format!(
+            "expected `<year> <day> <part> <answer> <verdict>`, found `{}`",
+            line.trim()
+        ));
+    };
+    Ok(Guess {
+        year: year.parse().map_err(|_| format!("invalid year `{year}`"))?,
+        day: day.parse().map_err(|_| format!("invalid day `{day}`"))?,
+        part: part.parse()?,
+        answer: answer.parse()?,
+        verdict: verdict.parse()?,
+    })
+}
+
+impl Client {
+    fn history_path(&self) -> PathBuf {
+        self.config().state_dir.join("guesses.txt")
+    }
+
+    /// Posts an answer, unless the guess history already rules it out, and records the verdict.
+    pub fn submit(&self, day: u8, part: Part, answer: Answer) -> Result<Verdict, String> {
+        let year = self.config().year;
+        let path = self.history_path();
+        let mut history = History::load(&path)?;
+        history
+            .check(year, day, part, answer)
+            .map_err(|reason| format!("not submitting: {reason}"))?;
+
+        let url = format!("{}/{year}/day/{day}/answer", self.base_url());
+        let request = self.authorized(self.agent().post(&url))?;
+        let level = part.to_string();
+        let value = answer.to_string();
+        let html = self
+            .send(request, Some(&[("level", &level), ("answer", &value)]))?
+            .into_string()
+            .map_err(|err| format!("failed to read the response from {url}: {err}"))?;
+        let verdict = Verdict::from_response(&html)
+            .ok_or_else(|| format!("couldn't make sense of the response from {url}"))?;
+
+        history.guesses.push(Guess {
+            year,
+            day,
+            part,
+            answer,
+            verdict,
+        });
+        history.save(&path)?;
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(answer: i64, verdict: Verdict) -> Guess {
+        Guess {
+            year: 2023,
+            day: 10,
+            part: Part::Two,
+            answer: Answer::from(answer),
+            verdict,
+        }
+    }
+
+    #[test]
+    fn test_from_response() {
+        let page = |message: &str| format!("<main>\n<article><p>{message}</p></article>\n</main>");
+        assert_eq!(
+            Verdict::from_response(&page(
+                "That's the right answer!  You are one gold star closer."
+            )),
+            Some(Verdict::Right)
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck..."
+            )),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            Verdict::from_response(&page("That's not the right answer.  If you're stuck...")),
+            Some(Verdict::Wrong)
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 1m 23s left to wait."
+            )),
+            Some(Verdict::Wait(Duration::from_secs(83)))
+        );
+        assert_eq!(
+            Verdict::from_response(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Some(Verdict::WrongLevel)
+        );
+        assert_eq!(Verdict::from_response(&page("Hello")), None);
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let history = History {
+            guesses: vec![
+                guess(512, Verdict::TooHigh),
+                guess(400, Verdict::Wait(Duration::from_secs(30))),
+                guess(483, Verdict::Right),
+            ],
+        };
+        let path = std::env::temp_dir().join(format!("aoc-guesses-{}.txt", std::process::id()));
+        history.save(&path).unwrap();
+        assert_eq!(History::load(&path), Ok(history));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_check() {
+        let history = History {
+            guesses: vec![
+                guess(512, Verdict::TooHigh),
+                guess(100, Verdict::TooLow),
+                guess(300, Verdict::Wrong),
+                guess(200, Verdict::Wait(Duration::from_secs(60))),
+            ],
+        };
+        let check = |answer: i64| history.check(2023, 10, Part::Two, Answer::from(answer));
+
+        assert_eq!(check(483), Ok(()));
+        assert_eq!(check(200), Ok(()));
+        assert_eq!(
+            check(300),
+            Err("300 was already rejected as wrong".to_string())
+        );
+        assert_eq!(
+            check(600),
+            Err("600 is higher than 512, which was too high".to_string())
+        );
+        assert_eq!(
+            check(512),
+            Err("512 was already rejected as too-high".to_string())
+        );
+        assert_eq!(
+            check(99),
+            Err("99 is lower than 100, which was too low".to_string())
+        );
+        assert_eq!(
+            history.check(2023, 10, Part::One, Answer::from(600)),
+            Ok(())
+        );
+
+        let solved = History {
+            guesses: vec![guess(483, Verdict::Right)],
+        };
+        assert!(solved
+            .check(2023, 10, Part::Two, Answer::from(484))
+            .is_err());
+    }
+}
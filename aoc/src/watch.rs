@@ -0,0 +1,477 @@
+//! `aoc watch`: polls a day's sources and inputs, and on every change rebuilds the runner and
+//! re-runs the day against each of its inputs, showing the new answers next to the previous ones.
+//!
+//! The runner can't reload its own code, so each run is a fresh `aoc run --format json` of the
+//! rebuilt binary, whose answer or error is read back from its output.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{IsTerminal, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use common::{input::Source, Answer, Json, Part};
+
+use crate::{
+    answers::Expected,
+    run_all::{format_table, Status},
+    workspace_root, Day,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// When and how big each file under some paths was last seen, to tell when any of them change.
+pub type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+/// Every file under `paths`, which may be files or directories. Paths that don't exist are
+/// skipped, so a folder can be created while it's being watched.
+pub fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    let mut pending = paths.to_vec();
+    while let Some(path) = pending.pop() {
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if let Ok(entries) = fs::read_dir(&path) {
+                pending.extend(entries.flatten().map(|entry| entry.path()));
+            }
+        } else {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            snapshot.insert(path, (modified, metadata.len()));
+        }
+    }
+    snapshot
+}
+
+/// Files that were added, removed or modified between two snapshots.
+pub fn changed_files(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed = after
+        .iter()
+        .filter(|&(path, seen)| before.get(path) != Some(seen))
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed.sort();
+    changed
+}
+
+/// One part run against one input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub part: Part,
+    /// The input's file name within the day's `inputs/` folder.
+    pub file: String,
+    /// The answer, or why there isn't one.
+    pub outcome: Result<Answer, String>,
+}
+
+fn status(outcome: &Result<Answer, String>, expected: Option<Answer>) -> Status {
+    match (outcome, expected) {
+        (Ok(answer), Some(expected)) if *answer == expected => Status::Pass,
+        (Ok(_), Some(_)) => Status::Fail,
+        (Err(_), Some(_)) => Status::Error,
+        (_, None) => Status::Unchecked,
+    }
+}
+
+/// How to show the answer a run gave, or the run's error.
+fn display(outcome: &Result<Answer, String>) -> String {
+    match outcome {
+        Ok(answer) => answer.to_string(),
+        Err(message) => message.clone(),
+    }
+}
+
+/// Lays out `runs` as a table next to the answers from the `previous` round, if there was one.
+/// With `color`, rows that disagree with `answers.txt` are red and rows whose answer changed
+/// are bold.
+pub fn render(runs: &[Run], previous: &[Run], expected: &[Expected], color: bool) -> String {
+    let expected_for = |run: &Run| {
+        expected
+            .iter()
+            .find(|e| e.file == run.file && e.part == run.part)
+            .map(|e| e.answer)
+    };
+    let previous_for = |run: &Run| {
+        previous
+            .iter()
+            .find(|old| old.file == run.file && old.part == run.part)
+            .map(|old| &old.outcome)
+    };
+
+    let rows = runs
+        .iter()
+        .map(|run| {
+            let status = match (status(&run.outcome, expected_for(run)), expected_for(run)) {
+                (Status::Fail, Some(expected)) => format!("FAIL (expected {expected})"),
+                (status, _) => status.to_string(),
+            };
+            [
+                run.part.to_string(),
+                run.file.clone(),
+                previous_for(run).map(display).unwrap_or_default(),
+                display(&run.outcome),
+                status,
+            ]
+        })
+        .collect::<Vec<_>>();
+    let table = format_table(
+        &["part", "input", "previous", "answer", "status"],
+        &rows,
+        &[0],
+    );
+    if !color {
+        return table;
+    }
+
+    let mut lines = table.lines();
+    let mut colored = format!("{}\n", lines.next().unwrap_or_default());
+    for (run, line) in runs.iter().zip(lines) {
+        let mismatch = matches!(
+            status(&run.outcome, expected_for(run)),
+            Status::Fail | Status::Error
+        );
+        let changed = previous_for(run).is_some_and(|old| *old != run.outcome);
+        colored += &match (mismatch, changed) {
+            (true, true) => format!("\x1b[1;31m{line}\x1b[0m\n"),
+            (true, false) => format!("\x1b[31m{line}\x1b[0m\n"),
+            (false, true) => format!("\x1b[1m{line}\x1b[0m\n"),
+            (false, false) => format!("{line}\n"),
+        };
+    }
+    colored
+}
+
+/// The answer from one `aoc run --format json` of a single part and input, given its output:
+/// a line with the answer or the error, or only an error on stderr if it didn't get that far.
+fn parse_output(stdout: &str, stderr: &str) -> Result<Answer, String> {
+    let Some(line) = stdout.lines().last() else {
+        let message = stderr
+            .lines()
+            .find_map(|line| line.strip_prefix("error: "))
+            .unwrap_or("failed without a message");
+        return Err(message.to_string());
+    };
+    let run = Json::parse(line).map_err(|err| format!("unexpected output `{line}`: {err}"))?;
+    match (run.get("answer"), run.get("error")) {
+        (Some(&Json::Int(answer)), _) => Ok(Answer::from(answer)),
+        // Parse errors go on to show the line they're in, which doesn't fit in the table.
+        (_, Some(Json::Text(error))) => Err(error.lines().next().unwrap_or_default().to_string()),
+        _ => Err(format!("unexpected output `{line}`")),
+    }
+}
+
+/// The runner built by `cargo build -p aoc`, with or without `--release`.
+fn runner_path(release: bool) -> PathBuf {
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("target"));
+    let profile = if release { "release" } else { "debug" };
+    target
+        .join(profile)
+        .join(format!("aoc{}", env::consts::EXE_SUFFIX))
+}
+
+/// Rebuilds the runner, letting cargo's output through so that compile errors show up.
+fn build(release: bool) -> Result<(), String> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command
+        .args(["build", "-q", "-p", "aoc"])
+        .current_dir(workspace_root())
+        .stdin(Stdio::null());
+    if release {
+        command.arg("--release");
+    }
+    let status = command
+        .status()
+        .map_err(|err| format!("failed to run cargo: {err}"))?;
+    match status.success() {
+        true => Ok(()),
+        false => Err("build failed".to_string()),
+    }
+}
+
+/// Runs one part against one input in a fresh process, killing it after `timeout`.
+fn run_one(
+    runner: &Path,
+    day: &Day,
+    part: Part,
+    path: &Path,
+    timeout: Duration,
+) -> Result<Answer, String> {
+    let mut child = Command::new(runner)
+        .arg("run")
+        .arg(day.number.to_string())
+        .arg(part.to_string())
+        .arg(path)
+        .args(["--format", "json"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("failed to run {}: {err}", runner.display()))?;
+
+    // Read both pipes while the run goes on, as one that fills up would stall it.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if start.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(err) => return Err(format!("failed to wait for the run: {err}")),
+        }
+    }
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    parse_output(&stdout, &stderr)
+}
+
+/// Reads all of `pipe` on another thread, until the process writing to it closes it.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut text);
+        }
+        text
+    })
+}
+
+/// Runs each of `parts` against every input in the day's `inputs/` folder.
+fn run_inputs(
+    day: &Day,
+    parts: &[Part],
+    release: bool,
+    timeout: Duration,
+) -> Result<Vec<Run>, String> {
+    let runner = runner_path(release);
+    let sources = Source::expand(&day.dir().join("inputs"))?;
+    let mut runs = Vec::new();
+    for &part in parts {
+        for source in &sources {
+            let Source::File(path) = source else {
+                unreachable!("directories only expand to files");
+            };
+            runs.push(Run {
+                part,
+                file: path.file_name().unwrap().to_string_lossy().into_owned(),
+                outcome: run_one(&runner, day, part, path, timeout),
+            });
+        }
+    }
+    Ok(runs)
+}
+
+fn relative(path: &Path) -> String {
+    path.strip_prefix(workspace_root())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Watches the day until interrupted.
+pub fn watch(day: &Day, parts: &[Part], release: bool, timeout: Duration) -> Result<(), String> {
+    let watched = [
+        day.dir().join("src"),
+        day.dir().join("inputs"),
+        day.answers_path(),
+    ];
+    let color = std::io::stdout().is_terminal();
+    println!(
+        "watching {}; press Ctrl-C to stop",
+        watched
+            .iter()
+            .map(|path| relative(path))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut seen = snapshot(&watched);
+    let mut previous = Vec::new();
+    loop {
+        match build(release) {
+            Ok(()) => {
+                let runs = run_inputs(day, parts, release, timeout)?;
+                let expected = day.expected_answers()?;
+                print!("{}", render(&runs, &previous, &expected, color));
+                previous = runs;
+            }
+            Err(message) => println!("{message}; waiting for changes"),
+        }
+
+        let changed = loop {
+            thread::sleep(POLL_INTERVAL);
+            let now = snapshot(&watched);
+            let changed = changed_files(&seen, &now);
+            seen = now;
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        println!(
+            "\nchanged: {}",
+            changed
+                .iter()
+                .map(|path| relative(path))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    fn run(part: Part, file: &str, outcome: Result<i32, &str>) -> Run {
+        Run {
+            part,
+            file: file.to_string(),
+            outcome: outcome.map(Answer::from).map_err(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let previous = [
+            run(Part::One, "example1.txt", Ok(142)),
+            run(Part::One, "input.txt", Ok(53000)),
+        ];
+        let runs = [
+            run(Part::One, "example1.txt", Ok(142)),
+            run(Part::One, "input.txt", Ok(53194)),
+            run(Part::Two, "example1.txt", Err("parse error")),
+        ];
+        let expected = [
+            Expected {
+                file: "example1.txt".to_string(),
+                part: Part::One,
+                answer: Answer::from(142),
+            },
+            Expected {
+                file: "input.txt".to_string(),
+                part: Part::One,
+                answer: Answer::from(53193),
+            },
+        ];
+
+        assert_eq!(
+            render(&runs, &previous, &expected, false),
+            "\
+part  input         previous  answer       status
+   1  example1.txt  142       142          pass
+   1  input.txt     53000     53194        FAIL (expected 53193)
+   2  example1.txt            parse error  -
+"
+        );
+        let colored = render(&runs, &previous, &expected, true);
+        let lines = colored.lines().collect::<Vec<_>>();
+        assert!(!lines[1].contains('\x1b'));
+        assert!(lines[2].starts_with("\x1b[1;31m"));
+    }
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(
+            parse_output(r#"{"day":1,"part":2,"answer":281,"extras":{}}"#, ""),
+            Ok(Answer::from(281))
+        );
+        assert_eq!(
+            parse_output(
+                r#"{"day":1,"part":1,"error":"expected a digit at line 2, column 1\n  |"}"#,
+                "error: failed to parse x.txt: expected a digit at line 2, column 1"
+            ),
+            Err("expected a digit at line 2, column 1".to_string())
+        );
+        assert_eq!(
+            parse_output(
+                r#"{"day":1,"part":1,"error":"panicked: index out of bounds"}"#,
+                "thread 'main' panicked at day01/src/lib.rs:1:1:\nindex out of bounds"
+            ),
+            Err("panicked: index out of bounds".to_string())
+        );
+        assert_eq!(
+            parse_output(
+                "",
+                "note: x.txt: converted 1 carriage return(s)\nerror: no such file"
+            ),
+            Err("no such file".to_string())
+        );
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let dir = env::temp_dir().join(format!("aoc-watch-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "fn a() {}").unwrap();
+        fs::write(dir.join("answers.txt"), "").unwrap();
+        let paths = [dir.join("src"), dir.join("inputs"), dir.join("answers.txt")];
+
+        let before = snapshot(&paths);
+        assert_eq!(before.len(), 2);
+        assert_eq!(
+            changed_files(&before, &snapshot(&paths)),
+            Vec::<PathBuf>::new()
+        );
+
+        fs::write(dir.join("src/lib.rs"), "fn ab() {}").unwrap();
+        fs::create_dir_all(dir.join("inputs")).unwrap();
+        fs::write(dir.join("inputs/example1.txt"), "1").unwrap();
+        fs::remove_file(dir.join("answers.txt")).unwrap();
+        assert_eq!(
+            changed_files(&before, &snapshot(&paths)),
+            vec![
+                dir.join("answers.txt"),
+                dir.join("inputs/example1.txt"),
+                dir.join("src/lib.rs"),
+            ]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_one_with_lots_of_output() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = env::temp_dir().join(format!("aoc-watch-run-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // Far more than a pipe holds, on both pipes, before the answer.
+        let runner = dir.join("runner");
+        fs::write(
+            &runner,
+            "#!/bin/sh\n\
+             head -c 1000000 /dev/zero | tr '\\0' x >&2\n\
+             head -c 1000000 /dev/zero | tr '\\0' '\\n'\n\
+             echo '{\"day\":1,\"part\":1,\"answer\":42}'\n",
+        )
+        .unwrap();
+        fs::set_permissions(&runner, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let day = Day::get(1).unwrap();
+        let outcome = run_one(&runner, day, Part::One, &dir, Duration::from_secs(10));
+        assert_eq!(outcome, Ok(Answer::from(42)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+//! Runs every day against every input listed in its `answers.txt` manifest.
+
+use std::{collections::BTreeSet, fs};
+
+use aoc::DAYS;
+
+#[test]
+fn test_expected_answers() {
+    let mut failures = Vec::new();
+    let mut checked = 0;
+
+    for day in DAYS {
+        for expected in day.expected_answers().unwrap() {
+            let path = day.expected_input(&expected);
+            let label = format!("{} {} part {}", day.name, expected.file, expected.part);
+            let input = match fs::read_to_string(&path) {
+                Ok(input) => input,
+                Err(err) => {
+                    failures.push(format!("{label}: failed to read {}: {err}", path.display()));
+                    continue;
+                }
+            };
+
+            checked += 1;
+            match day.solve(expected.part, &input) {
+                Ok(answer) if answer == expected.answer => {}
+                Ok(answer) => failures.push(format!(
+                    "{label}: expected {}, got {answer}",
+                    expected.answer
+                )),
+                Err(err) => failures.push(format!("{label}: {err}")),
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {checked} answers were wrong:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_inputs_pass_check() {
+    let mut failures = Vec::new();
+
+    for day in DAYS {
+        let runs = day
+            .expected_answers()
+            .unwrap()
+            .iter()
+            .map(|expected| (day.expected_input(expected), expected.part))
+            .collect::<BTreeSet<_>>();
+        for (path, part) in runs {
+            let Ok(input) = fs::read_to_string(&path) else {
+                continue;
+            };
+            for problem in day.check(&input, Some(part)) {
+                failures.push(format!(
+                    "{} {} part {part}: {problem}",
+                    day.name,
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} problems in inputs:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,95 @@
+//! `aoc fetch` against a local stand-in for the site.
+
+mod stub;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use aoc::client::{Client, Config, Fetched};
+use stub::Stub;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc-fetch-{}-{name}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn client(stub: &Stub, dir: &Path, session: Option<&str>, min_interval: Duration) -> Client {
+    Client::new(Config {
+        session: session.map(str::to_string),
+        base_url: format!("{}/", stub.base_url),
+        year: 2023,
+        min_interval,
+        state_dir: dir.join("state"),
+    })
+}
+
+fn input_stub() -> Stub {
+    Stub::start(|request| match request.header("Cookie") {
+        Some("session=secret") => (200, format!("input for {}\n", request.path)),
+        _ => (
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.".to_string(),
+        ),
+    })
+}
+
+#[test]
+fn test_fetch_downloads_once() {
+    let stub = input_stub();
+    let dir = scratch_dir("once");
+    let client = client(&stub, &dir, Some("secret"), Duration::ZERO);
+    let dest = dir.join("day07/inputs/input.txt");
+
+    assert_eq!(client.fetch_input(7, &dest), Ok(Fetched::Downloaded));
+    assert_eq!(
+        fs::read_to_string(&dest).unwrap(),
+        "input for /2023/day/7/input\n"
+    );
+    // Nothing is left behind from writing it.
+    let files = fs::read_dir(dest.parent().unwrap()).unwrap().count();
+    assert_eq!(files, 1);
+    assert_eq!(client.fetch_input(7, &dest), Ok(Fetched::Cached));
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert!(requests[0].header("User-Agent").is_some());
+}
+
+#[test]
+fn test_fetch_waits_between_requests() {
+    let stub = input_stub();
+    let dir = scratch_dir("interval");
+    let client = client(&stub, &dir, Some("secret"), Duration::from_millis(300));
+
+    client.fetch_input(1, &dir.join("day01.txt")).unwrap();
+    client.fetch_input(2, &dir.join("day02.txt")).unwrap();
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].received - requests[0].received >= Duration::from_millis(250));
+}
+
+#[test]
+fn test_fetch_errors() {
+    let stub = input_stub();
+    let dir = scratch_dir("errors");
+    let dest = dir.join("input.txt");
+
+    let err = client(&stub, &dir, None, Duration::ZERO)
+        .fetch_input(3, &dest)
+        .unwrap_err();
+    assert!(err.contains("no session token"), "{err}");
+
+    let err = client(&stub, &dir, Some("expired"), Duration::ZERO)
+        .fetch_input(3, &dest)
+        .unwrap_err();
+    assert!(err.contains("returned 400"), "{err}");
+    assert!(!dest.exists());
+}
@@ -0,0 +1,49 @@
+//! Checks that every day's input generator makes inputs that pass the day's checks and that it
+//! can solve, the same way each time for a given seed.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use aoc::{Part, DAYS};
+
+#[test]
+fn test_generated_inputs_are_solvable() {
+    let mut failures = Vec::new();
+
+    for day in DAYS {
+        for size in [1, 2, 5, 20] {
+            for seed in 0..20 {
+                let label = format!("{} --seed {seed} --size {size}", day.name);
+                let Some(input) = day.generate(seed, size) else {
+                    failures.push(format!("{label}: no generator"));
+                    continue;
+                };
+                for part in Part::ALL {
+                    for problem in day.check(&input, Some(part)) {
+                        failures.push(format!("{label} part {part}: {problem}"));
+                    }
+                    match panic::catch_unwind(AssertUnwindSafe(|| day.solve(part, &input))) {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(err)) => failures.push(format!("{label} part {part}: {err}")),
+                        Err(_) => failures.push(format!("{label} part {part}: panicked")),
+                    }
+                }
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} generated inputs failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_generators_are_deterministic() {
+    for day in DAYS {
+        let input = day.generate(7, 10);
+        assert_eq!(day.generate(7, 10), input, "{}", day.name);
+        assert_ne!(day.generate(8, 10), input, "{}", day.name);
+    }
+}
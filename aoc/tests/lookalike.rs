@@ -0,0 +1,53 @@
+//! Checks that every day can make a look-alike of each of its recorded inputs that's the same
+//! size, passes the day's checks and solves, the same way each time for a given seed.
+
+use std::path::PathBuf;
+
+use aoc::{lookalike, Part, DAYS};
+use common::input::Source;
+
+#[test]
+fn test_lookalikes_are_solvable() {
+    let mut failures = Vec::new();
+
+    for day in DAYS {
+        // Only the parts recorded for an input are solved, since some examples are only for one.
+        let mut files = Vec::<(PathBuf, Vec<Part>)>::new();
+        for expected in day.expected_answers().unwrap() {
+            let file = day.expected_input(&expected);
+            match files.iter_mut().find(|(seen, _)| *seen == file) {
+                Some((_, parts)) => parts.push(expected.part),
+                None => files.push((file, vec![expected.part])),
+            }
+        }
+        for (file, parts) in files {
+            let label = format!("{} {}", day.name, file.display());
+            let input = Source::File(file).read().unwrap().text;
+            let made = match lookalike::make(day, &input, 0) {
+                Ok(made) => made,
+                Err(err) => {
+                    failures.push(format!("{label}: {err}"));
+                    continue;
+                }
+            };
+            if made.lines().count() != input.trim_end().lines().count() {
+                failures.push(format!("{label}: has a different number of lines"));
+            }
+            if let Err(err) = lookalike::solve(day, &made, &parts) {
+                failures.push(format!("{label}: {err}"));
+            }
+            if lookalike::make(day, &input, 0).as_ref() != Ok(&made) {
+                failures.push(format!(
+                    "{label}: made a different look-alike the second time"
+                ));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} look-alikes failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
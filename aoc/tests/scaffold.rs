@@ -0,0 +1,82 @@
+//! `aoc new` in a throwaway workspace, checking that the day it creates compiles and passes its
+//! tests as it is.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use aoc::{scaffold, workspace_root};
+
+/// Copies the files under `from` to `to`, leaving out build output.
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        if entry.file_name() == "target" {
+            continue;
+        }
+        match entry.file_type().unwrap().is_dir() {
+            true => copy_dir(&path, &to.join(entry.file_name())),
+            false => {
+                fs::copy(&path, to.join(entry.file_name())).unwrap();
+            }
+        }
+    }
+}
+
+/// A workspace with just `common`, and the runner files that `aoc new` registers days in.
+fn workspace() -> PathBuf {
+    let root = env::temp_dir().join(format!("aoc-scaffold-{}", process::id()));
+    let _ = fs::remove_dir_all(&root);
+    copy_dir(&workspace_root().join("common"), &root.join("common"));
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nresolver = \"2\"\nmembers = [\n    \"common\",\n]\n",
+    )
+    .unwrap();
+    // The runner isn't a member, so it isn't built, but the day is still added to it.
+    fs::create_dir_all(root.join("aoc/src")).unwrap();
+    fs::write(
+        root.join("aoc/Cargo.toml"),
+        "[dependencies]\nday01 = { path = \"../day01\" }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("aoc/src/lib.rs"),
+        "days! {\n    1 => day01::Day01,\n}\n",
+    )
+    .unwrap();
+    // The same versions of dependencies as here, so nothing needs fetching.
+    if let Ok(lock) = fs::read(workspace_root().join("Cargo.lock")) {
+        fs::write(root.join("Cargo.lock"), lock).unwrap();
+    }
+    root
+}
+
+#[test]
+fn test_new_day_passes_its_tests() {
+    let root = workspace();
+    scaffold::new_day(&root, 12).unwrap();
+
+    // Kept between runs, so dependencies are only built once.
+    let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("scaffold");
+    let output = Command::new(env!("CARGO"))
+        .args(["test", "--quiet", "-p", "day12"])
+        .env("CARGO_TARGET_DIR", &target)
+        .current_dir(&root)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "cargo test failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(fs::read_to_string(root.join("Cargo.toml"))
+        .unwrap()
+        .contains("\"day12\""));
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,140 @@
+//! `aoc serve` over a real socket on localhost.
+
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use aoc::serve::{serve, Limits};
+
+fn start(limits: Limits) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, limits));
+    addr
+}
+
+/// Sends `request` as is and returns the response's status and body.
+fn send(addr: SocketAddr, request: &[u8]) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .unwrap();
+    let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+    (status, body)
+}
+
+fn post(addr: SocketAddr, path: &str, body: &str) -> (u16, String) {
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    send(addr, request.as_bytes())
+}
+
+#[test]
+fn test_serve_solves() {
+    let addr = start(Limits::default());
+
+    let (status, body) = post(
+        addr,
+        "/day/6/part/2",
+        "Time:      7  15   30\nDistance:  9  40  200",
+    );
+    assert_eq!(status, 200);
+    assert!(
+        body.starts_with(r#"{"day":6,"part":2,"answer":71503,"timings":{"parse_ns":"#),
+        "{body}"
+    );
+
+    // Inputs are normalized like files are.
+    let (status, body) = post(addr, "/day/9/part/1", "0 3 6 9 12 15\r\n");
+    assert_eq!(status, 200);
+    assert!(body.contains(r#""answer":18,"#), "{body}");
+
+    let (status, body) = post(addr, "/day/4/part/1", "Card 1: 41 48 / 83 86");
+    assert_eq!(status, 422);
+    assert!(
+        body.contains(r#""error":{"message":"expected ` |` at line 1, column 14"#),
+        "{body}"
+    );
+
+    let (status, body) = post(addr, "/day/42/part/1", "");
+    assert_eq!(
+        (status, body.as_str()),
+        (404, r#"{"error":"unknown day `42`"}"#)
+    );
+    let (status, _) = send(addr, b"GET /day/6/part/1 HTTP/1.1\r\n\r\n");
+    assert_eq!(status, 405);
+}
+
+#[test]
+fn test_serve_limits() {
+    let addr = start(Limits {
+        max_body: 64,
+        timeout: Duration::from_millis(300),
+        ..Limits::default()
+    });
+
+    let (status, _) = post(addr, "/day/9/part/1", &"1 2 3\n".repeat(20));
+    assert_eq!(status, 413);
+
+    // The client stops sending partway through the body.
+    let start = Instant::now();
+    let (status, _) = send(
+        addr,
+        b"POST /day/9/part/1 HTTP/1.1\r\nContent-Length: 20\r\n\r\n1 2",
+    );
+    assert_eq!(status, 408);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // A network whose start loops on itself never reaches `ZZZ`.
+    let start = Instant::now();
+    let (status, body) = post(
+        addr,
+        "/day/8/part/1",
+        "L\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)",
+    );
+    assert_eq!(
+        (status, body.as_str()),
+        (503, r#"{"error":"timed out after 0.3s"}"#)
+    );
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // Other requests are still served.
+    let (status, _) = post(addr, "/day/9/part/1", "1 2 3");
+    assert_eq!(status, 200);
+}
+
+#[test]
+fn test_serve_bounds_connections() {
+    let addr = start(Limits {
+        timeout: Duration::from_millis(300),
+        max_connections: 1,
+        ..Limits::default()
+    });
+
+    // A client that never finishes its request holds the only slot until it times out, and the
+    // next one waits for it.
+    let start = Instant::now();
+    let mut stalled = TcpStream::connect(addr).unwrap();
+    stalled
+        .write_all(b"POST /day/9/part/1 HTTP/1.1\r\nContent-Length: 20\r\n\r\n1 2")
+        .unwrap();
+    thread::sleep(Duration::from_millis(50));
+    let (status, _) = post(addr, "/day/9/part/1", "1 2 3");
+    assert_eq!(status, 200);
+    assert!(start.elapsed() >= Duration::from_millis(300));
+
+    let mut response = String::new();
+    stalled.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 408"), "{response}");
+}
@@ -0,0 +1,87 @@
+//! Checks that every day solving its input a line at a time gives the same answers that way as
+//! when parsing it whole, on every recorded input and on generated ones, read either from memory
+//! or through a reader.
+
+use std::fs;
+
+use aoc::{Day, Part, DAYS};
+use common::bytes::Lines;
+
+/// Streams `part` of `text` both ways, failing unless both give `expected`.
+fn compare(day: &Day, part: Part, text: &str, expected: &str, failures: &mut Vec<String>) {
+    let from_bytes = day.stream_lines(part, &mut Lines::from_bytes(text));
+    let Some(from_bytes) = from_bytes else {
+        failures.push(format!("part {part} doesn't stream"));
+        return;
+    };
+    let from_reader = day
+        .stream_lines(part, &mut Lines::from_reader(text.as_bytes()))
+        .unwrap();
+    for (how, streamed) in [("bytes", from_bytes), ("reader", from_reader)] {
+        let streamed = streamed.map_or_else(|err| err.to_string(), |answer| answer.to_string());
+        if streamed != expected {
+            failures.push(format!(
+                "part {part} from {how}: expected {expected}, streamed {streamed}"
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_streaming_matches_recorded_answers() {
+    let mut failures = Vec::new();
+    for day in DAYS {
+        for expected in day.expected_answers().unwrap() {
+            let path = day.expected_input(&expected);
+            // Read as saved, since streaming does its own normalizing.
+            let text = fs::read_to_string(&path).unwrap();
+            let mut found = Vec::new();
+            compare(
+                day,
+                expected.part,
+                &text,
+                &expected.answer.to_string(),
+                &mut found,
+            );
+            failures.extend(
+                found
+                    .into_iter()
+                    .map(|failure| format!("{} {}: {failure}", day.name, path.display())),
+            );
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} streamed runs failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_streaming_matches_parsing_generated_inputs() {
+    let mut failures = Vec::new();
+    for day in DAYS {
+        for seed in 0..20 {
+            let input = day.generate(seed, 20).unwrap();
+            for part in Part::ALL {
+                let Ok(answer) = day.solve(part, &input) else {
+                    continue;
+                };
+                let mut found = Vec::new();
+                compare(day, part, &input, &answer.to_string(), &mut found);
+                failures.extend(
+                    found
+                        .into_iter()
+                        .map(|failure| format!("{} --seed {seed}: {failure}", day.name)),
+                );
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} streamed runs failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,120 @@
+//! `aoc submit` against a local stand-in for the site.
+
+mod stub;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use aoc::{
+    client::{Client, Config},
+    submit::{History, Verdict},
+};
+use common::{Answer, Part};
+use stub::Stub;
+
+const RIGHT: i64 = 483;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("aoc-submit-{}-{name}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Judges `answer` against [`RIGHT`], like the site would.
+fn answer_stub() -> Stub {
+    Stub::start(|request| {
+        let answer: i64 = request
+            .body
+            .split('&')
+            .find_map(|field| field.strip_prefix("answer="))
+            .unwrap()
+            .parse()
+            .unwrap();
+        let message = match answer {
+            RIGHT => "That's the right answer!".to_string(),
+            a if a > RIGHT => "That's not the right answer; your answer is too high.".to_string(),
+            _ => "That's not the right answer; your answer is too low.".to_string(),
+        };
+        (
+            200,
+            format!("<main><article><p>{message}</p></article></main>"),
+        )
+    })
+}
+
+fn client(stub: &Stub, dir: &Path) -> Client {
+    Client::new(Config {
+        session: Some("secret".to_string()),
+        base_url: stub.base_url.clone(),
+        year: 2023,
+        min_interval: Duration::ZERO,
+        state_dir: dir.to_path_buf(),
+    })
+}
+
+#[test]
+fn test_submit_records_and_guards_guesses() {
+    let stub = answer_stub();
+    let dir = scratch_dir("guards");
+    let client = client(&stub, &dir);
+    let submit = |answer: i64| client.submit(10, Part::Two, Answer::from(answer));
+
+    assert_eq!(submit(600), Ok(Verdict::TooHigh));
+    assert_eq!(submit(100), Ok(Verdict::TooLow));
+    assert!(submit(700).unwrap_err().contains("which was too high"));
+    assert!(submit(50).unwrap_err().contains("which was too low"));
+    assert!(submit(600).unwrap_err().contains("already rejected"));
+    assert_eq!(submit(RIGHT), Ok(Verdict::Right));
+    assert!(submit(RIGHT).unwrap_err().contains("already accepted"));
+
+    let requests = stub.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/2023/day/10/answer");
+    assert_eq!(requests[0].body, "level=2&answer=600");
+    assert_eq!(requests[0].header("Cookie"), Some("session=secret"));
+
+    let history = History::load(&dir.join("guesses.txt")).unwrap();
+    let verdicts = history
+        .guesses
+        .iter()
+        .map(|guess| (guess.answer.value(), guess.verdict))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        verdicts,
+        vec![
+            (600, Verdict::TooHigh),
+            (100, Verdict::TooLow),
+            (RIGHT as i128, Verdict::Right),
+        ]
+    );
+}
+
+#[test]
+fn test_submit_rate_limited() {
+    let stub = Stub::start(|_| {
+        (
+            200,
+            "<article><p>You gave an answer too recently; you have to wait after submitting an \
+             answer before trying again.  You have 2m 5s left to wait.</p></article>"
+                .to_string(),
+        )
+    });
+    let dir = scratch_dir("wait");
+    let client = client(&stub, &dir);
+
+    assert_eq!(
+        client.submit(3, Part::One, Answer::from(42)),
+        Ok(Verdict::Wait(Duration::from_secs(125)))
+    );
+    // A guess that was never judged can be sent again.
+    assert_eq!(
+        client.submit(3, Part::One, Answer::from(42)),
+        Ok(Verdict::Wait(Duration::from_secs(125)))
+    );
+}
@@ -0,0 +1,152 @@
+//! A global allocator that counts what it hands out. It only counts once a binary installs it:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: common::alloc::CountingAllocator = common::alloc::CountingAllocator;
+//! ```
+//!
+//! The counters are process-wide, so [`measure`] only gives meaningful numbers when nothing else
+//! is allocating on another thread at the same time.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator, counting allocations, bytes allocated and peak live bytes.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_alloc(size: usize) {
+        COUNT.fetch_add(1, Relaxed);
+        BYTES.fetch_add(size, Relaxed);
+        let live = LIVE.fetch_add(size, Relaxed) + size;
+        PEAK.fetch_max(live, Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        LIVE.fetch_sub(size, Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::record_dealloc(layout.size());
+    }
+
+    /// Counted as freeing the old block and allocating the new one, since that's what a growing
+    /// `Vec` costs when the block can't be extended in place.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// What a piece of code allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+    /// Number of allocations, including reallocations.
+    pub count: usize,
+    /// Total bytes allocated, whether or not they were freed again.
+    pub bytes: usize,
+    /// The most memory that was live at once, on top of what was already live beforehand.
+    pub peak: usize,
+}
+
+impl fmt::Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, {} peak",
+            self.count,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+/// Runs `f`, counting what it allocates. Everything is zero unless [`CountingAllocator`] is the
+/// global allocator.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    let count = COUNT.load(Relaxed);
+    let bytes = BYTES.load(Relaxed);
+    let live = LIVE.load(Relaxed);
+    PEAK.store(live, Relaxed);
+
+    let result = f();
+
+    let allocations = Allocations {
+        count: COUNT.load(Relaxed) - count,
+        bytes: BYTES.load(Relaxed) - bytes,
+        peak: PEAK.load(Relaxed).saturating_sub(live),
+    };
+    (result, allocations)
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b < 1 << 10 => format!("{b} B"),
+        b if b < 1 << 20 => format!("{:.1} KiB", b as f64 / (1 << 10) as f64),
+        b => format!("{:.1} MiB", b as f64 / (1 << 20) as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    // Other tests allocate concurrently, so these only check lower bounds.
+    #[test]
+    fn test_measure() {
+        let (sum, allocations) = measure(|| {
+            let mut numbers = Vec::new();
+            numbers.extend(0..1000u64);
+            let big = vec![0u8; 1 << 20];
+            drop(big);
+            numbers.iter().sum::<u64>()
+        });
+
+        assert_eq!(sum, 499500);
+        assert!(allocations.count >= 2);
+        assert!(allocations.bytes >= (1 << 20) + 8000);
+        assert!(allocations.peak >= 1 << 20);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0 MiB");
+    }
+}
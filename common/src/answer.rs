@@ -0,0 +1,60 @@
+use std::{fmt, str::FromStr};
+
+/// A puzzle answer. Every solution returns some integer, but the widths vary from day to day, so
+/// they're all widened into an `i128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Answer(i128);
+
+impl Answer {
+    pub fn value(&self) -> i128 {
+        self.0
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Answer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .map(Answer)
+            .map_err(|_| format!("invalid answer `{s}`, expected an integer"))
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(value: $int) -> Self {
+                    Answer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_int() {
+        assert_eq!(Answer::from(7u32), Answer::from(7i64));
+        assert_eq!(Answer::from(-3i32).to_string(), "-3");
+        assert_eq!(Answer::from(u64::MAX).value(), u64::MAX as i128);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("-42".parse(), Ok(Answer::from(-42)));
+        assert!("4x".parse::<Answer>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Known answers, recorded per day in an `answers.txt` manifest next to the `inputs/` folder. Each
+//! line names an input file, a part and the answer it should produce:
+//!
+//! ```text
+//! # file        part  answer
+//! example1.txt  1     142
+//! input.txt     2     54249
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored.
+
+use crate::{Answer, Part};
+
+/// One line of a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    /// The input's file name, relative to the day's `inputs/` folder.
+    pub file: String,
+    pub part: Part,
+    pub answer: Answer,
+}
+
+pub fn parse(manifest: &str) -> Result<Vec<Expected>, String> {
+    manifest
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| parse_line(line).map_err(|err| format!("line {}: {err}", i + 1)))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<Expected, String> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let [file, part, answer] = fields.as_slice() else {
+        return Err(format!(
+            "expected `<file> <part> <answer>`, found `{}`",
+            line.trim()
+        ));
+    };
+
+    Ok(Expected {
+        file: file.to_string(),
+        part: part.parse()?,
+        answer: answer.parse()?,
+    })
+}
+
+/// `manifest` with `answers` recorded for `file`, replacing the answers it already has for those
+/// parts and adding the others at the end. Everything else is left as it was.
+pub fn update(manifest: &str, file: &str, answers: &[(Part, Answer)]) -> String {
+    let mut missing = answers.to_vec();
+    let mut lines = manifest
+        .lines()
+        .map(|line| {
+            let is_entry = !line.trim_start().starts_with('#');
+            let replaced = match parse_line(line) {
+                Ok(expected) if is_entry && expected.file == file => missing
+                    .iter()
+                    .position(|&(part, _)| part == expected.part)
+                    .map(|i| missing.remove(i).1),
+                _ => None,
+            };
+            match replaced {
+                Some(answer) => {
+                    let line = line.trim_end();
+                    let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+                    format!("{}{answer}", &line[..start])
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+    lines.extend(
+        missing
+            .iter()
+            .map(|(part, answer)| format!("{file:<12}  {:<4}  {answer}", part.to_string())),
+    );
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let manifest = "# file part answer\n\nexample1.txt 1 142\n  input.txt\t2  -7\n";
+        assert_eq!(
+            parse(manifest),
+            Ok(vec![
+                Expected {
+                    file: "example1.txt".to_string(),
+                    part: Part::One,
+                    answer: Answer::from(142),
+                },
+                Expected {
+                    file: "input.txt".to_string(),
+                    part: Part::Two,
+                    answer: Answer::from(-7),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("example1.txt 1 142\nexample1.txt 3 1"),
+            Err("line 2: invalid part `3`, expected 1 or 2".to_string())
+        );
+        assert_eq!(
+            parse("example1.txt 1"),
+            Err("line 1: expected `<file> <part> <answer>`, found `example1.txt 1`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let manifest = "\
+# file        part  answer
+example1.txt  1     13
+input.txt     1     24542
+input.txt     2     8736438
+";
+        let answers = [(Part::Two, Answer::from(99)), (Part::One, Answer::from(-5))];
+        assert_eq!(
+            update(manifest, "input.txt", &answers),
+            "\
+# file        part  answer
+example1.txt  1     13
+input.txt     1     -5
+input.txt     2     99
+"
+        );
+        assert_eq!(
+            update(manifest, "other.txt", &answers[..1]),
+            format!("{manifest}other.txt     2     99\n")
+        );
+        assert_eq!(
+            update("", "x.txt", &answers[..1]),
+            "x.txt         2     99\n"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Budgets: limits on how long a solution may run, for loops and searches that never end on some
+//! inputs, like a day 8 walk towards a `ZZZ` it can't reach. Such loops call [`step`] once per
+//! iteration, and a caller that wants a limit runs the solution with [`run`], which stops it once
+//! it takes too many steps, runs out of time or is cancelled, and returns why instead.
+//!
+//! Without [`run`], [`step`] only counts, so solutions behave as before. Like [`crate::trace`],
+//! budgets are per thread: steps taken on other threads don't count.
+
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// How many steps pass between checks of the clock and the cancel flag, which cost more than
+/// counting.
+const CHECK_EVERY: u64 = 1 << 10;
+
+/// A flag for stopping a solution from another thread, e.g. once its caller has given up
+/// waiting for it.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops whatever is running with this flag at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Limits to run a solution within. The default has none.
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    /// Most steps allowed.
+    pub steps: Option<u64>,
+    /// Longest time allowed, counted from the start of [`run`].
+    pub time: Option<Duration>,
+    pub cancel: Option<Cancel>,
+}
+
+/// Why a solution was stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exceeded {
+    Steps(u64),
+    Time(Duration),
+    Cancelled,
+}
+
+impl fmt::Display for Exceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exceeded::Steps(steps) => write!(f, "did not terminate within {steps} steps"),
+            Exceeded::Time(time) => {
+                write!(f, "did not terminate within {:.1}s", time.as_secs_f64())
+            }
+            Exceeded::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Exceeded {}
+
+/// The clock and cancel flag of the innermost [`run`].
+struct Checks {
+    deadline: Option<(Instant, Duration)>,
+    cancel: Option<Cancel>,
+}
+
+thread_local! {
+    static STEPS: Cell<u64> = const { Cell::new(0) };
+    static MAX_STEPS: Cell<u64> = const { Cell::new(u64::MAX) };
+    static CHECKS: RefCell<Option<Checks>> = const { RefCell::new(None) };
+}
+
+/// Stops the solution, unwinding back to [`run`] without going through the panic hook.
+fn stop(exceeded: Exceeded) -> ! {
+    panic::resume_unwind(Box::new(exceeded))
+}
+
+/// Counts one step of a loop that might not end, stopping the solution if that uses up its
+/// budget.
+pub fn step() {
+    let steps = STEPS.get() + 1;
+    STEPS.set(steps);
+    let max_steps = MAX_STEPS.get();
+    if steps > max_steps {
+        stop(Exceeded::Steps(max_steps));
+    }
+    if steps.is_multiple_of(CHECK_EVERY) {
+        let exceeded = CHECKS.with_borrow(|checks| match checks {
+            Some(Checks {
+                cancel: Some(cancel),
+                ..
+            }) if cancel.is_cancelled() => Some(Exceeded::Cancelled),
+            Some(Checks {
+                deadline: Some((deadline, time)),
+                ..
+            }) if Instant::now() >= *deadline => Some(Exceeded::Time(*time)),
+            _ => None,
+        });
+        if let Some(exceeded) = exceeded {
+            stop(exceeded);
+        }
+    }
+}
+
+/// Puts back the budget from before a [`run`], however it ends.
+struct Restore {
+    steps: u64,
+    max_steps: u64,
+    checks: Option<Checks>,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        STEPS.set(self.steps);
+        MAX_STEPS.set(self.max_steps);
+        CHECKS.set(self.checks.take());
+    }
+}
+
+/// Runs `f` within `budget`, or returns why it was stopped. Runs can be nested, and the inner one
+/// gets its own budget. Panics other than a budget running out carry on unwinding.
+pub fn run<T>(budget: &Budget, f: impl FnOnce() -> T) -> Result<T, Exceeded> {
+    let _restore = Restore {
+        steps: STEPS.replace(0),
+        max_steps: MAX_STEPS.replace(budget.steps.unwrap_or(u64::MAX)),
+        checks: CHECKS.replace(Some(Checks {
+            deadline: budget.time.map(|time| (Instant::now() + time, time)),
+            cancel: budget.cancel.clone(),
+        })),
+    };
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| match payload.downcast() {
+        Ok(exceeded) => *exceeded,
+        Err(payload) => panic::resume_unwind(payload),
+    })
+}
+
+/// The message a caught panic was raised with, for reporting a run that panicked rather than
+/// ran out of budget.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    /// Counts down from `n`, or forever if it's zero.
+    fn count_down(mut n: u64) -> u64 {
+        let mut steps = 0;
+        while n != 1 {
+            step();
+            n = n.wrapping_sub(1);
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let budget = Budget {
+            steps: Some(100),
+            ..Budget::default()
+        };
+        assert_eq!(run(&budget, || count_down(101)), Ok(100));
+        assert_eq!(run(&budget, || count_down(0)), Err(Exceeded::Steps(100)));
+        assert_eq!(
+            Exceeded::Steps(100).to_string(),
+            "did not terminate within 100 steps"
+        );
+        // Without a budget, steps are only counted.
+        assert_eq!(count_down(1000), 999);
+    }
+
+    #[test]
+    fn test_time_limit_and_cancel() {
+        let budget = Budget {
+            time: Some(Duration::from_millis(10)),
+            ..Budget::default()
+        };
+        assert_eq!(
+            run(&budget, || count_down(0)),
+            Err(Exceeded::Time(Duration::from_millis(10)))
+        );
+
+        let cancel = Cancel::new();
+        let budget = Budget {
+            cancel: Some(cancel.clone()),
+            ..Budget::default()
+        };
+        let looping = thread::spawn(move || run(&budget, || count_down(0)));
+        cancel.cancel();
+        assert_eq!(looping.join().unwrap(), Err(Exceeded::Cancelled));
+    }
+
+    #[test]
+    fn test_nested_runs() {
+        let outer = Budget {
+            steps: Some(10),
+            ..Budget::default()
+        };
+        let inner = Budget {
+            steps: Some(5),
+            ..Budget::default()
+        };
+        let result = run(&outer, || {
+            step();
+            let inner = run(&inner, || count_down(0));
+            (inner, count_down(11))
+        });
+        assert_eq!(result, Err(Exceeded::Steps(10)));
+        assert_eq!(
+            run(&outer, || run(&inner, || count_down(0))),
+            Ok(Err(Exceeded::Steps(5)))
+        );
+        assert!(panic::catch_unwind(|| run(&outer, || panic!("not a budget"))).is_err());
+    }
+}
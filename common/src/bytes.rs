@@ -0,0 +1,428 @@
+//! Byte-level input, for inputs too big to read into a `String` and parse whole. [`open`] reads a
+//! [`Source`] one line at a time, through a memory map for plain files and a buffered reader
+//! otherwise, and a [`Cursor`] parses a line in place without copying it. Days whose answers can
+//! be built up a line at a time implement [`crate::Solution::stream`] with them, in memory that
+//! doesn't grow with the input.
+//!
+//! Lines come out the way [`crate::input::normalize`] would leave them: ended by `\n`, `\r\n` or
+//! a lone `\r`, without trailing spaces and tabs or a byte order mark, and with no blank lines at
+//! the end.
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader},
+};
+
+use flate2::read::GzDecoder;
+use memmap2::Mmap;
+
+use crate::{input::Source, ParseError};
+
+/// Where [`Lines`] gets its raw lines from.
+trait Raw {
+    /// Moves on to the next line, returning `false` at the end of the input.
+    fn advance(&mut self) -> io::Result<bool>;
+
+    /// The current line, possibly still ending in `\n`, `\r\n` or `\r`.
+    fn line(&self) -> &[u8];
+}
+
+/// How long the first line of `bytes` is, counting its line ending, or `None` if it doesn't end.
+/// Like [`crate::input::normalize`], a `\r` that isn't part of a `\r\n` ends a line too.
+fn line_len(bytes: &[u8]) -> Option<usize> {
+    let i = bytes.iter().position(|&b| b == b'\n' || b == b'\r')?;
+    match &bytes[i..] {
+        [b'\r', b'\n', ..] => Some(i + 2),
+        _ => Some(i + 1),
+    }
+}
+
+/// Lines of bytes that are all in memory already, such as a memory map.
+struct InMemory<B> {
+    bytes: B,
+    start: usize,
+    end: usize,
+}
+
+impl<B: AsRef<[u8]>> Raw for InMemory<B> {
+    fn advance(&mut self) -> io::Result<bool> {
+        let bytes = self.bytes.as_ref();
+        if self.end >= bytes.len() {
+            return Ok(false);
+        }
+        self.start = self.end;
+        self.end = line_len(&bytes[self.start..]).map_or(bytes.len(), |len| self.start + len);
+        Ok(true)
+    }
+
+    fn line(&self) -> &[u8] {
+        &self.bytes.as_ref()[self.start..self.end]
+    }
+}
+
+/// Lines read one at a time into a buffer that's reused. What's read up to a `\n` may hold more
+/// than one line if there are lone `\r`s in it, which are passed on one at a time.
+struct Buffered<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl<R: BufRead> Raw for Buffered<R> {
+    fn advance(&mut self) -> io::Result<bool> {
+        if self.end == self.buffer.len() {
+            self.buffer.clear();
+            self.end = 0;
+            if self.reader.read_until(b'\n', &mut self.buffer)? == 0 {
+                return Ok(false);
+            }
+        }
+        self.start = self.end;
+        let rest = &self.buffer[self.start..];
+        self.end = self.start + line_len(rest).unwrap_or(rest.len());
+        Ok(true)
+    }
+
+    fn line(&self) -> &[u8] {
+        &self.buffer[self.start..self.end]
+    }
+}
+
+/// `line` without its line ending (`\n`, `\r\n` or `\r`), trailing spaces and tabs, or byte order
+/// mark if it's the first.
+fn clean(line: &[u8], first: bool) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let end = line
+        .iter()
+        .rposition(|&b| b != b' ' && b != b'\t')
+        .map_or(0, |i| i + 1);
+    let line = &line[..end];
+    match first {
+        true => line.strip_prefix("\u{feff}".as_bytes()).unwrap_or(line),
+        false => line,
+    }
+}
+
+/// An input's lines, numbered from 1. Each line is only valid until the next one is read.
+pub struct Lines<'a> {
+    raw: Box<dyn Raw + 'a>,
+    number: usize,
+    /// Blank lines read ahead of the current one, which are only passed on once a line that
+    /// isn't blank shows they aren't at the end.
+    blanks: usize,
+    /// Whether the raw current line hasn't been passed on yet.
+    held: bool,
+    error: Option<io::Error>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(raw: impl Raw + 'a) -> Self {
+        Lines {
+            raw: Box::new(raw),
+            number: 0,
+            blanks: 0,
+            held: false,
+            error: None,
+        }
+    }
+
+    /// Lines of text that's already in memory.
+    pub fn from_bytes(bytes: impl AsRef<[u8]> + 'a) -> Self {
+        Lines::new(InMemory {
+            bytes,
+            start: 0,
+            end: 0,
+        })
+    }
+
+    /// Lines read through `reader`, holding only one at a time.
+    pub fn from_reader(reader: impl BufRead + 'a) -> Self {
+        Lines::new(Buffered {
+            reader,
+            buffer: Vec::new(),
+            start: 0,
+            end: 0,
+        })
+    }
+
+    /// The next line and its number, or `None` at the end of the input or if reading failed,
+    /// which [`Lines::take_error`] tells apart.
+    pub fn next_line(&mut self) -> Option<(usize, &[u8])> {
+        if !self.held && self.blanks == 0 {
+            loop {
+                match self.raw.advance() {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        self.blanks = 0;
+                        return None;
+                    }
+                    Err(err) => {
+                        self.blanks = 0;
+                        self.error = Some(err);
+                        return None;
+                    }
+                }
+                let first = self.number + self.blanks == 0;
+                if clean(self.raw.line(), first).is_empty() {
+                    self.blanks += 1;
+                } else {
+                    self.held = true;
+                    break;
+                }
+            }
+        }
+
+        self.number += 1;
+        if self.blanks > 0 {
+            self.blanks -= 1;
+            return Some((self.number, &[]));
+        }
+        self.held = false;
+        Some((self.number, clean(self.raw.line(), self.number == 1)))
+    }
+
+    /// The error that ended the lines early, if any.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+}
+
+/// Opens `source` for reading a line at a time: plain files are memory-mapped, and gzipped files
+/// and stdin go through a buffered reader.
+pub fn open(source: &Source) -> Result<Lines<'static>, String> {
+    let error = |err| format!("failed to read {source}: {err}");
+    match source {
+        Source::Stdin => Ok(Lines::from_reader(io::stdin().lock())),
+        Source::File(path) if path.extension().is_some_and(|ext| ext == "gz") => {
+            let file = fs::File::open(path).map_err(error)?;
+            Ok(Lines::from_reader(BufReader::new(GzDecoder::new(file))))
+        }
+        Source::File(path) => {
+            let file = fs::File::open(path).map_err(error)?;
+            // SAFETY: the map is only read, and the input isn't expected to change while it's
+            // being solved. If it does, answers may be wrong, but reads stay in bounds.
+            let map = unsafe { Mmap::map(&file) }.map_err(error)?;
+            Ok(Lines::from_bytes(map))
+        }
+    }
+}
+
+/// Checks that a row of a grid read a line at a time is as wide as the first row, whose width
+/// `width` keeps. Like [`crate::parse::grid`], with one byte per cell.
+pub fn grid_row(number: usize, line: &[u8], width: &mut Option<usize>) -> Result<(), ParseError> {
+    let width = *width.get_or_insert(line.len());
+    match line.len() == width {
+        true => Ok(()),
+        false => Err(ParseError::in_line(
+            number,
+            line,
+            line.len().min(width),
+            format!("a row of {width} cells"),
+        )),
+    }
+}
+
+/// A position in one line, for parsing it in place. Errors point at the position they happened.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    line: &'a [u8],
+    number: usize,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// A cursor at the start of `line`, which is line `number` of the input.
+    pub fn new(number: usize, line: &'a [u8]) -> Self {
+        Cursor {
+            line,
+            number,
+            pos: 0,
+        }
+    }
+
+    /// What's left of the line.
+    pub fn rest(&self) -> &'a [u8] {
+        &self.line[self.pos..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.line.len()
+    }
+
+    /// An error at the cursor.
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        ParseError::in_line(self.number, self.line, self.pos, expected)
+    }
+
+    /// Skips `tag` if the line continues with it.
+    pub fn eat(&mut self, tag: &str) -> bool {
+        let found = self.rest().starts_with(tag.as_bytes());
+        if found {
+            self.pos += tag.len();
+        }
+        found
+    }
+
+    /// Skips `tag`, or fails if the line doesn't continue with it.
+    pub fn expect(&mut self, tag: &str) -> Result<(), ParseError> {
+        match self.eat(tag) {
+            true => Ok(()),
+            false => Err(self.error(format!("`{tag}`"))),
+        }
+    }
+
+    /// Skips the next `len` bytes and returns them, if the line has that many left.
+    pub fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let taken = self.rest().get(..len)?;
+        self.pos += len;
+        Some(taken)
+    }
+
+    /// Skips any spaces and tabs, returning whether there were some.
+    pub fn spaces(&mut self) -> bool {
+        let start = self.pos;
+        while matches!(self.line.get(self.pos), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+
+    /// Skips a word of ASCII letters and returns it.
+    pub fn word(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.line.get(self.pos).is_some_and(u8::is_ascii_alphabetic) {
+            self.pos += 1;
+        }
+        &self.line[start..self.pos]
+    }
+
+    /// A number without a sign.
+    pub fn unsigned(&mut self) -> Result<u64, ParseError> {
+        let start = self.pos;
+        let mut value = 0u64;
+        while let Some(digit) = self.line.get(self.pos).filter(|b| b.is_ascii_digit()) {
+            value = value
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(u64::from(*digit - b'0')))
+                .ok_or_else(|| {
+                    Cursor {
+                        pos: start,
+                        ..*self
+                    }
+                    .error("a smaller number")
+                })?;
+            self.pos += 1;
+        }
+        match self.pos > start {
+            true => Ok(value),
+            false => Err(self.error("a number")),
+        }
+    }
+
+    /// A number with an optional `-` or `+` sign.
+    pub fn signed(&mut self) -> Result<i64, ParseError> {
+        let start = self.clone();
+        let negative = self.eat("-");
+        if !negative {
+            self.eat("+");
+        }
+        let magnitude = self.unsigned().map_err(|_| start.error("a number"))?;
+        let value = match negative {
+            true => 0i64.checked_sub_unsigned(magnitude),
+            false => i64::try_from(magnitude).ok(),
+        };
+        value.ok_or_else(|| start.error("a smaller number"))
+    }
+
+    /// The next of a list of numbers separated by spaces, or `None` at the end of the line.
+    pub fn next_number(&mut self) -> Option<Result<i64, ParseError>> {
+        self.spaces();
+        (!self.is_empty()).then(|| self.signed())
+    }
+
+    /// Fails unless the whole line has been parsed.
+    pub fn end(&self) -> Result<(), ParseError> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(self.error("end of line")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::normalize;
+
+    fn collect(mut lines: Lines<'_>) -> Vec<(usize, String)> {
+        let mut collected = Vec::new();
+        while let Some((number, line)) = lines.next_line() {
+            collected.push((number, String::from_utf8(line.to_vec()).unwrap()));
+        }
+        collected
+    }
+
+    #[test]
+    fn test_lines_are_normalized() {
+        let raw = "\u{feff}a \r\n\n\t\nb\t\r\nc\n\n \n";
+        let expected = [(1, "a"), (2, ""), (3, ""), (4, "b"), (5, "c")]
+            .map(|(number, line)| (number, line.to_string()))
+            .to_vec();
+        assert_eq!(collect(Lines::from_bytes(raw.as_bytes())), expected);
+        assert_eq!(collect(Lines::from_reader(raw.as_bytes())), expected);
+        assert_eq!(collect(Lines::from_bytes(b"x")), [(1, "x".to_string())]);
+
+        // Lone carriage returns end lines, as when normalizing.
+        let raw = "a\rb \r\n\rc\r\r\nd\r";
+        let expected = normalize(raw)
+            .text
+            .split('\n')
+            .zip(1..)
+            .map(|(line, number)| (number, line.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected.len(), 6);
+        assert_eq!(collect(Lines::from_bytes(raw.as_bytes())), expected);
+        assert_eq!(collect(Lines::from_reader(raw.as_bytes())), expected);
+        assert_eq!(collect(Lines::from_bytes(b"")), []);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut cursor = Cursor::new(3, b"Game 12:  -4 +5 x");
+        assert!(cursor.eat("Game"));
+        assert!(cursor.spaces());
+        assert_eq!(cursor.unsigned(), Ok(12));
+        assert_eq!(cursor.expect(":"), Ok(()));
+        assert_eq!(cursor.next_number(), Some(Ok(-4)));
+        assert_eq!(cursor.next_number(), Some(Ok(5)));
+        let error = cursor.next_number().unwrap().unwrap_err();
+        assert_eq!((error.line, error.column), (3, 17));
+        assert_eq!(error.expected, "a number");
+        assert_eq!(error.line_text, "Game 12:  -4 +5 x");
+        assert_eq!(cursor.end().unwrap_err().expected, "end of line");
+
+        let mut cursor = Cursor::new(1, b"AKQ 5");
+        assert_eq!(cursor.take(3), Some(&b"AKQ"[..]));
+        assert_eq!(cursor.take(3), None);
+
+        let mut cursor = Cursor::new(1, b"-9223372036854775808 9223372036854775808");
+        assert_eq!(cursor.next_number(), Some(Ok(i64::MIN)));
+        let error = cursor.next_number().unwrap().unwrap_err();
+        assert_eq!(
+            (error.column, error.expected.as_str()),
+            (22, "a smaller number")
+        );
+    }
+
+    #[test]
+    fn test_grid_row() {
+        let mut width = None;
+        assert_eq!(grid_row(1, b"#..", &mut width), Ok(()));
+        let error = grid_row(2, b"#...", &mut width).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "a row of 3 cells");
+        assert_eq!(grid_row(3, b"..", &mut width).unwrap_err().column, 3);
+    }
+}
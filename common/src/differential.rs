@@ -0,0 +1,290 @@
+//! Differential testing: a day pairs a fast solver with a slow, obviously correct reference, and
+//! the two are run against many small generated cases. When they disagree, the case is shrunk
+//! to a minimal one that still shows the difference before it's reported.
+//!
+//! Days register their checks with [`crate::Solution::differentials`].
+
+use std::{
+    env,
+    fmt::{self, Debug},
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{budget::panic_message, rng::Rng};
+
+pub const DEFAULT_CASES: usize = 500;
+pub const DEFAULT_SEED: u64 = 2023;
+
+/// A fast implementation and a reference that should always agree with it.
+pub struct Differential<T, R> {
+    pub name: &'static str,
+    /// Builds a random case. Cases should be small, so the reference stays quick and mismatches
+    /// are readable.
+    pub generate: fn(&mut Rng) -> T,
+    /// Smaller variants of a case to try when shrinking, most promising first. Every variant must
+    /// still be a valid case.
+    pub shrink: fn(&T) -> Vec<T>,
+    pub fast: fn(&T) -> R,
+    pub reference: fn(&T) -> R,
+}
+
+/// What one side of a comparison produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output<R> {
+    Returned(R),
+    Panicked(String),
+}
+
+impl<R: Debug> fmt::Display for Output<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Output::Returned(value) => write!(f, "{value:?}"),
+            Output::Panicked(message) => write!(f, "panicked: {message}"),
+        }
+    }
+}
+
+/// A case on which the fast and reference implementations disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch<T, R> {
+    pub name: &'static str,
+    pub seed: u64,
+    /// How many cases were tried before this one failed.
+    pub tried: usize,
+    /// How many times the original failing case was successfully shrunk.
+    pub shrinks: usize,
+    pub case: T,
+    pub fast: Output<R>,
+    pub reference: Output<R>,
+}
+
+impl<T: Debug, R: Debug> fmt::Display for Mismatch<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: fast and reference disagree (seed {}, case {}, shrunk {} times)",
+            self.name,
+            self.seed,
+            self.tried + 1,
+            self.shrinks
+        )?;
+        writeln!(f, "case: {:#?}", self.case)?;
+        writeln!(f, "fast:      {}", self.fast)?;
+        write!(f, "reference: {}", self.reference)
+    }
+}
+
+fn run<T, R>(f: fn(&T) -> R, case: &T) -> Output<R> {
+    match panic::catch_unwind(AssertUnwindSafe(|| f(case))) {
+        Ok(value) => Output::Returned(value),
+        Err(payload) => Output::Panicked(panic_message(payload)),
+    }
+}
+
+impl<T: Debug, R: PartialEq + Debug> Differential<T, R> {
+    /// Both outputs for `case`, if they differ.
+    fn compare(&self, case: &T) -> Option<(Output<R>, Output<R>)> {
+        let fast = run(self.fast, case);
+        let reference = run(self.reference, case);
+        (fast != reference).then_some((fast, reference))
+    }
+
+    /// Compares the two implementations on `cases` generated cases, returning the first mismatch
+    /// after shrinking it. Panics count as output, so a case that only one side panics on is a
+    /// mismatch too.
+    pub fn check(&self, cases: usize, seed: u64) -> Result<(), Mismatch<T, R>> {
+        let mut rng = Rng::new(seed);
+        for tried in 0..cases {
+            let case = (self.generate)(&mut rng);
+            if let Some((fast, reference)) = self.compare(&case) {
+                return Err(self.shrink(Mismatch {
+                    name: self.name,
+                    seed,
+                    tried,
+                    shrinks: 0,
+                    case,
+                    fast,
+                    reference,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Greedily replaces the case with the first smaller variant that still mismatches, until
+    /// none do.
+    fn shrink(&self, mut mismatch: Mismatch<T, R>) -> Mismatch<T, R> {
+        'shrinking: loop {
+            for case in (self.shrink)(&mismatch.case) {
+                if let Some((fast, reference)) = self.compare(&case) {
+                    mismatch = Mismatch {
+                        shrinks: mismatch.shrinks + 1,
+                        case,
+                        fast,
+                        reference,
+                        ..mismatch
+                    };
+                    continue 'shrinking;
+                }
+            }
+            return mismatch;
+        }
+    }
+}
+
+/// A [`Differential`] with its types erased, so that a day can register several.
+pub trait Check {
+    fn name(&self) -> &'static str;
+
+    /// Like [`Differential::check`], with the mismatch already formatted.
+    fn check(&self, cases: usize, seed: u64) -> Result<(), String>;
+}
+
+impl<T: Debug, R: PartialEq + Debug> Check for Differential<T, R> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn check(&self, cases: usize, seed: u64) -> Result<(), String> {
+        Differential::check(self, cases, seed).map_err(|mismatch| mismatch.to_string())
+    }
+}
+
+/// Runs every check, panicking with the shrunk mismatches if any fail. The number of cases and
+/// the seed can be changed with `AOC_DIFF_CASES` and `AOC_DIFF_SEED`. Meant to be called from
+/// a test.
+pub fn assert_all(checks: Vec<Box<dyn Check>>) {
+    let cases = env::var("AOC_DIFF_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(DEFAULT_CASES);
+    let seed = env::var("AOC_DIFF_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(DEFAULT_SEED);
+
+    let failures = checks
+        .iter()
+        .filter_map(|check| check.check(cases, seed).err())
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n\n"));
+}
+
+/// Variants of `items` with a chunk of elements removed, trying larger chunks first.
+pub fn shrink_vec<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut variants = Vec::new();
+    let mut chunk = items.len().div_ceil(2);
+    while chunk > 0 {
+        for start in (0..items.len()).step_by(chunk) {
+            let mut variant = items[..start].to_vec();
+            variant.extend_from_slice(&items[(start + chunk).min(items.len())..]);
+            variants.push(variant);
+        }
+        chunk /= 2;
+    }
+    variants
+}
+
+/// Smaller numbers to try in place of `n`, closest to `target` first and never past it.
+pub fn shrink_int(n: i64, target: i64) -> Vec<i64> {
+    let mut candidates = Vec::new();
+    let mut distance = n - target;
+    while distance != 0 {
+        candidates.push(n - distance);
+        distance /= 2;
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum_of_squares(n: &u64) -> u64 {
+        (1..=*n).map(|i| i * i).sum()
+    }
+
+    #[test]
+    fn test_agreeing_implementations_pass() {
+        let differential = Differential {
+            name: "sum of squares",
+            generate: |rng| rng.below(100),
+            shrink: |_| vec![],
+            fast: |&n| n * (n + 1) * (2 * n + 1) / 6,
+            reference: sum_of_squares,
+        };
+        assert_eq!(differential.check(200, 1), Ok(()));
+    }
+
+    #[test]
+    fn test_mismatches_are_shrunk() {
+        // Wrong once there's an element over 10 in the list.
+        let differential = Differential {
+            name: "sum",
+            generate: |rng| (0..rng.below(20)).map(|_| rng.range(0..=30)).collect(),
+            shrink: |items: &Vec<i64>| {
+                let mut variants = shrink_vec(items);
+                for (i, &item) in items.iter().enumerate() {
+                    variants.extend(shrink_int(item, 0).into_iter().map(|smaller| {
+                        let mut variant = items.clone();
+                        variant[i] = smaller;
+                        variant
+                    }));
+                }
+                variants
+            },
+            fast: |items| items.iter().map(|&item| item.min(10)).sum::<i64>(),
+            reference: |items| items.iter().sum::<i64>(),
+        };
+
+        let mismatch = differential.check(100, 1).unwrap_err();
+        assert_eq!(mismatch.case, vec![11]);
+        assert_eq!(mismatch.fast, Output::Returned(10));
+        assert_eq!(mismatch.reference, Output::Returned(11));
+        assert!(mismatch.shrinks > 0);
+        assert!(mismatch
+            .to_string()
+            .starts_with("sum: fast and reference disagree (seed 1, case"));
+    }
+
+    #[test]
+    fn test_panics_are_mismatches() {
+        let differential = Differential {
+            name: "checked",
+            generate: |rng| rng.range(-5..=5),
+            shrink: |&n| shrink_int(n, 0),
+            fast: |&n| {
+                assert!(n >= 0, "negative");
+                n
+            },
+            reference: |&n| n,
+        };
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let mismatch = differential.check(100, 1);
+        panic::set_hook(previous_hook);
+
+        let mismatch = mismatch.unwrap_err();
+        assert_eq!(mismatch.case, -1);
+        assert_eq!(mismatch.fast, Output::Panicked("negative".to_string()));
+    }
+
+    #[test]
+    fn test_shrink_helpers() {
+        assert_eq!(shrink_int(10, 0), vec![0, 5, 8, 9]);
+        assert_eq!(shrink_int(-4, 1), vec![1, -2, -3]);
+        assert_eq!(shrink_int(3, 3), Vec::<i64>::new());
+        assert_eq!(shrink_vec(&[1]), vec![Vec::<i32>::new()]);
+        assert_eq!(
+            shrink_vec(&[1, 2, 3, 4]),
+            vec![
+                vec![3, 4],
+                vec![1, 2],
+                vec![2, 3, 4],
+                vec![1, 3, 4],
+                vec![1, 2, 4],
+                vec![1, 2, 3]
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::{error::Error, fmt};
+
+/// A parse failure, pointing at the line and column where the input stopped making sense.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// The full text of the offending line.
+    pub line_text: String,
+    /// What the parser was looking for, e.g. "a number" or "`Distance:`".
+    pub expected: String,
+}
+
+impl ParseError {
+    /// An error at the start of `at`, which must be a subslice of `source`.
+    pub fn new(source: &str, at: &str, expected: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+        debug_assert!(
+            offset <= source.len(),
+            "`at` should be a subslice of `source`"
+        );
+        Self::at_offset(source, offset.min(source.len()), expected)
+    }
+
+    /// An error at byte `offset` into `source`.
+    pub fn at_offset(source: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_text = source[line_start..].lines().next().unwrap_or_default();
+
+        ParseError {
+            line: source[..line_start].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            line_text: line_text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error at a 0-based `row` and `col`, for parsers that work on grids rather than offsets.
+    pub fn at_position(source: &str, row: usize, col: usize, expected: impl Into<String>) -> Self {
+        let line_text = source.lines().nth(row).unwrap_or_default();
+
+        ParseError {
+            line: row + 1,
+            column: col + 1,
+            line_text: line_text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error at byte `offset` into `text`, which is line `line` (1-based) of an input that's
+    /// read a line at a time.
+    pub fn in_line(line: usize, text: &[u8], offset: usize, expected: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column: String::from_utf8_lossy(&text[..offset]).chars().count() + 1,
+            line_text: String::from_utf8_lossy(text).into_owned(),
+            expected: expected.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let caret_indent = " ".repeat(self.column - 1);
+
+        writeln!(
+            f,
+            "expected {} at line {}, column {}",
+            self.expected, self.line, self.column
+        )?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(f, "{gutter} | {caret_indent}^")
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let source = "Time: 1 2\nDistance: 3 x";
+        let at = &source[source.find('x').unwrap()..];
+        let error = ParseError::new(source, at, "a number");
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 13);
+        assert_eq!(error.line_text, "Distance: 3 x");
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let source = "abc\n";
+        let error = ParseError::new(source, &source[4..], "more input");
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.line_text, "");
+    }
+
+    #[test]
+    fn test_display() {
+        let source = "Game 1: 3 blue\nGame 2: 8 purple";
+        let error = ParseError::new(source, &source[25..], "a colour");
+        assert_eq!(
+            error.to_string(),
+            "expected a colour at line 2, column 11\n  |\n2 | Game 2: 8 purple\n  |           ^"
+        );
+    }
+}
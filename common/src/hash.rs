@@ -0,0 +1,29 @@
+//! Hash maps and sets for solutions, with a fast hasher in place of the standard library's
+//! SipHash. Puzzle inputs aren't adversarial, so there's no need for SipHash's resistance to
+//! collision attacks, and the hasher isn't randomly seeded, so iterating over a map or set goes
+//! the same way on every run.
+//!
+//! Build them with `default()` or `collect()`, since `new()` is only for the standard hasher.
+
+pub use rustc_hash::FxBuildHasher as BuildHasher;
+
+pub type HashMap<K, V> = std::collections::HashMap<K, V, BuildHasher>;
+pub type HashSet<T> = std::collections::HashSet<T, BuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iteration_order_is_reproducible() {
+        let order = || {
+            (0..1000)
+                .collect::<HashSet<u32>>()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(), order());
+        let map = [("a", 1), ("b", 2)].into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(map["b"], 2);
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    borrow::Cow,
+    fmt, fs,
+    io::{self, IsTerminal, Read},
+    path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+
+/// Something [`normalize`] had to change to bring an input into the canonical form the parsers
+/// expect: `\n` line endings, no byte order mark, no trailing whitespace and no trailing newline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    ByteOrderMark,
+    /// Number of carriage returns converted: the `\r` of each `\r\n` line ending, and each lone
+    /// `\r`, which ends a line too.
+    LineEndings(usize),
+    /// Number of lines that had trailing spaces or tabs removed.
+    TrailingWhitespace(usize),
+    /// Number of trailing newlines removed from the end of the file.
+    TrailingNewlines(usize),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::ByteOrderMark => write!(f, "removed byte order mark"),
+            Change::LineEndings(count) => write!(f, "converted {count} carriage return(s)"),
+            Change::TrailingWhitespace(count) => {
+                write!(f, "trimmed trailing whitespace from {count} line(s)")
+            }
+            Change::TrailingNewlines(count) => write!(f, "removed {count} trailing newline(s)"),
+        }
+    }
+}
+
+/// Puzzle text in canonical form, along with what was changed to get it there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input<'a> {
+    pub text: Cow<'a, str>,
+    pub changes: Vec<Change>,
+}
+
+/// Brings puzzle text into the form every day's parser expects, however the file was saved. Text
+/// that's already canonical is borrowed rather than copied.
+pub fn normalize(raw: &str) -> Input<'_> {
+    let mut changes = Vec::new();
+
+    let text = match raw.strip_prefix('\u{feff}') {
+        Some(rest) => {
+            changes.push(Change::ByteOrderMark);
+            rest
+        }
+        None => raw,
+    };
+
+    let carriage_returns = text.matches('\r').count();
+    let trimmed_lines = text
+        .split(['\n', '\r'])
+        .filter(|line| line.ends_with([' ', '\t']))
+        .count();
+    let trailing_newlines = text.len() - text.trim_end_matches(['\n', '\r']).len();
+
+    if carriage_returns == 0 && trimmed_lines == 0 && trailing_newlines == 0 {
+        return Input {
+            text: Cow::Borrowed(text),
+            changes,
+        };
+    }
+
+    let lines = text
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .flat_map(|line| line.split('\r'))
+        .map(|line| line.trim_end_matches([' ', '\t']))
+        .collect::<Vec<_>>();
+    let mut normalized = lines.join("\n");
+    let newlines_before_trim = normalized.len();
+    normalized.truncate(normalized.trim_end_matches('\n').len());
+
+    if carriage_returns > 0 {
+        changes.push(Change::LineEndings(carriage_returns));
+    }
+    if trimmed_lines > 0 {
+        changes.push(Change::TrailingWhitespace(trimmed_lines));
+    }
+    if normalized.len() < newlines_before_trim {
+        changes.push(Change::TrailingNewlines(
+            newlines_before_trim - normalized.len(),
+        ));
+    }
+
+    Input {
+        text: Cow::Owned(normalized),
+        changes,
+    }
+}
+
+/// How an input can be given on the command line, for usage messages.
+pub const SOURCES_HELP: &str = "\
+<input> is a file (gzipped if it ends in `.gz`), a directory to run every `*.txt` file in, or `-`
+for stdin. Without one, input is read from stdin if it's piped.";
+
+/// Where puzzle input comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// The sources a command-line argument names: `-` is stdin, a directory is every `*.txt` file
+    /// in it, in name order, and anything else is a file.
+    pub fn expand(arg: &Path) -> Result<Vec<Source>, String> {
+        if arg == Path::new("-") {
+            return Ok(vec![Source::Stdin]);
+        }
+        if !arg.is_dir() {
+            return Ok(vec![Source::File(arg.to_path_buf())]);
+        }
+
+        let entries =
+            fs::read_dir(arg).map_err(|err| format!("failed to read {}: {err}", arg.display()))?;
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| format!("failed to read {}: {err}", arg.display()))?
+                .path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+                files.push(path);
+            }
+        }
+        if files.is_empty() {
+            return Err(format!("no *.txt files in {}", arg.display()));
+        }
+        files.sort();
+        Ok(files.into_iter().map(Source::File).collect())
+    }
+
+    /// Reads and normalizes the input.
+    pub fn read(&self) -> Result<Input<'static>, String> {
+        let raw = match self {
+            Source::Stdin => {
+                let mut raw = String::new();
+                io::stdin().read_to_string(&mut raw).map(|_| raw)
+            }
+            Source::File(path) if path.extension().is_some_and(|ext| ext == "gz") => {
+                fs::File::open(path).and_then(|file| {
+                    let mut raw = String::new();
+                    GzDecoder::new(file).read_to_string(&mut raw).map(|_| raw)
+                })
+            }
+            Source::File(path) => fs::read_to_string(path),
+        }
+        .map_err(|err| format!("failed to read {self}: {err}"))?;
+
+        let Input { text, changes } = normalize(&raw);
+        Ok(Input {
+            text: Cow::Owned(text.into_owned()),
+            changes,
+        })
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "<stdin>"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Whether stdin is redirected from a file or pipe. A terminal doesn't count, and neither does a
+/// character device like `/dev/null`, which is what stdin usually is when nothing is meant to be
+/// read from it.
+pub fn stdin_is_piped() -> bool {
+    if io::stdin().is_terminal() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+
+        if let Ok(metadata) = fs::metadata("/dev/stdin") {
+            return !metadata.file_type().is_char_device();
+        }
+    }
+    true
+}
+
+/// The sources named by a binary's optional input argument, falling back to piped stdin.
+pub fn sources(arg: Option<&str>) -> Result<Vec<Source>, String> {
+    match arg {
+        Some(arg) => Source::expand(Path::new(arg)),
+        None if stdin_is_piped() => Ok(vec![Source::Stdin]),
+        None => Err("no input given".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_input_is_borrowed() {
+        let input = normalize("a b\nc d");
+        assert!(matches!(input.text, Cow::Borrowed("a b\nc d")));
+        assert_eq!(input.changes, vec![]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let input = normalize("\u{feff}Time: 7  \r\nDistance: 9\t\r\n\r\n\r\n");
+        assert_eq!(input.text, "Time: 7\nDistance: 9");
+        assert_eq!(
+            input.changes,
+            vec![
+                Change::ByteOrderMark,
+                Change::LineEndings(4),
+                Change::TrailingWhitespace(2),
+                Change::TrailingNewlines(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blank_lines_between_sections_are_kept() {
+        let input = normalize("seeds: 1\r\n\r\nmap:\r\n1 2 3\n");
+        assert_eq!(input.text, "seeds: 1\n\nmap:\n1 2 3");
+    }
+
+    #[test]
+    fn test_lone_carriage_returns() {
+        let input = normalize("a\rb\r\nc");
+        assert_eq!(input.text, "a\nb\nc");
+        assert_eq!(input.changes, vec![Change::LineEndings(2)]);
+        assert_eq!(
+            input.changes[0].to_string(),
+            "converted 2 carriage return(s)"
+        );
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("common-input-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_expand() {
+        let dir = scratch_dir("expand");
+        for file in ["example2.txt", "example1.txt", "notes.md"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        fs::create_dir(dir.join("more.txt")).unwrap();
+
+        assert_eq!(Source::expand(Path::new("-")), Ok(vec![Source::Stdin]));
+        assert_eq!(
+            Source::expand(&dir),
+            Ok(vec![
+                Source::File(dir.join("example1.txt")),
+                Source::File(dir.join("example2.txt")),
+            ])
+        );
+        assert!(Source::expand(&dir.join("more.txt"))
+            .unwrap_err()
+            .starts_with("no *.txt files in"));
+    }
+
+    #[test]
+    fn test_read_gzip() {
+        use std::io::Write;
+
+        let dir = scratch_dir("gzip");
+        let path = dir.join("input.txt.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(fs::File::create(&path).unwrap(), Default::default());
+        encoder.write_all(b"Time: 7\r\nDistance: 9\r\n").unwrap();
+        encoder.finish().unwrap();
+
+        let input = Source::File(path).read().unwrap();
+        assert_eq!(input.text, "Time: 7\nDistance: 9");
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let err = Source::File(PathBuf::from("no/such/input.txt"))
+            .read()
+            .unwrap_err();
+        assert!(
+            err.starts_with("failed to read no/such/input.txt: "),
+            "{err}"
+        );
+    }
+}
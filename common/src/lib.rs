@@ -0,0 +1,22 @@
+pub mod alloc;
+mod answer;
+pub mod answers;
+pub mod budget;
+pub mod bytes;
+pub mod differential;
+mod error;
+pub mod hash;
+pub mod input;
+pub mod parse;
+pub mod rng;
+mod solution;
+pub mod trace;
+mod value;
+
+pub use answer::Answer;
+pub use error::ParseError;
+pub use solution::{
+    check_input, lookalike_input, main, print_problems, solve, solve_counted, solve_extras,
+    solve_timed, Part, Solution, StepAllocations, Timings,
+};
+pub use value::{Extras, Json, Value};
@@ -0,0 +1,366 @@
+//! Glue between the day parsers and [`ParseError`]. Parsers written with nom should use this
+//! module's [`IResult`], which tracks what was expected where, and hand their result to
+//! [`finish`].
+//!
+//! It also has combinators for the shapes that keep turning up in puzzle inputs: [`numbers`]
+//! separated by however many spaces, [`labelled`] lines like `Time: 7 15 30`, [`sections`]
+//! separated by blank lines, `key = (a, b)` [`record`]s and whole-input [`grid`]s.
+
+use std::borrow::Cow;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{space0, space1},
+    error::{context, ContextError, ErrorKind, FromExternalError},
+    multi::separated_list1,
+    sequence::{delimited, preceded},
+};
+
+use crate::ParseError;
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T, NomError<'a>>;
+
+/// The error type threaded through nom parsers: the remaining input where parsing failed, and
+/// what was expected there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NomError<'a> {
+    pub input: &'a str,
+    pub expected: Cow<'static, str>,
+}
+
+fn describe(kind: ErrorKind) -> Cow<'static, str> {
+    Cow::Borrowed(match kind {
+        ErrorKind::Digit => "a number",
+        ErrorKind::Alpha => "a letter",
+        ErrorKind::AlphaNumeric => "a letter or digit",
+        ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+        ErrorKind::Eof => "end of input",
+        ErrorKind::CrLf => "a line break",
+        ErrorKind::Tag => "literal text",
+        ErrorKind::MapRes | ErrorKind::Verify => "a valid value",
+        _ => return Cow::Owned(kind.description().to_lowercase()),
+    })
+}
+
+impl<'a> nom::error::ParseError<&'a str> for NomError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        NomError {
+            input,
+            expected: describe(kind),
+        }
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        NomError {
+            input,
+            expected: Cow::Owned(format!("{c:?}")),
+        }
+    }
+
+    /// Keeps whichever alternative got further, or lists both if they failed at the same spot.
+    fn or(self, other: Self) -> Self {
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => NomError {
+                input: self.input,
+                expected: Cow::Owned(format!("{} or {}", other.expected, self.expected)),
+            },
+        }
+    }
+}
+
+impl<'a> ContextError<&'a str> for NomError<'a> {
+    /// A context label only replaces the inner description when nothing inside it matched, so the
+    /// most specific description always wins.
+    fn add_context(input: &'a str, ctx: &'static str, other: Self) -> Self {
+        if other.input.len() == input.len() {
+            NomError {
+                input,
+                expected: Cow::Borrowed(ctx),
+            }
+        } else {
+            other
+        }
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for NomError<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _: E) -> Self {
+        nom::error::ParseError::from_error_kind(input, kind)
+    }
+}
+
+/// Turns a parser's result into a [`ParseError`] located in `source`, requiring the parser to have
+/// consumed everything. `source` must contain the text that was parsed.
+pub fn finish<'a, T>(source: &'a str, result: IResult<'a, T>) -> Result<T, ParseError> {
+    match result {
+        Ok(("", value)) => Ok(value),
+        Ok((rest, _)) => Err(ParseError::new(source, rest, "end of input")),
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+            Err(ParseError::new(source, error.input, error.expected))
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            Err(ParseError::at_offset(source, source.len(), "more input"))
+        }
+    }
+}
+
+/// Runs `parser` over every line of `source`, stopping at the first line that fails.
+pub fn lines<'a, T>(
+    source: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> Result<Vec<T>, ParseError> {
+    source
+        .lines()
+        .map(|line| finish(source, parser(line)))
+        .collect()
+}
+
+/// Like [`lines`], but keeps going past lines that fail, returning all of their errors. `text` is
+/// the part of `source` holding the lines, for inputs that start with something else.
+pub fn check_lines<'a, T>(
+    source: &'a str,
+    text: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> Result<Vec<T>, Vec<ParseError>> {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for line in text.lines() {
+        match finish(source, parser(line)) {
+            Ok(value) => values.push(value),
+            Err(error) => errors.push(error),
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(values),
+        false => Err(errors),
+    }
+}
+
+/// One or more numbers, or whatever else `number` parses, separated by runs of spaces or tabs,
+/// as in `41 48 83  6`. Padding before the first and after the last isn't skipped; wrap the list
+/// in [`padded`] for that.
+pub fn numbers<'a, T>(
+    number: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    separated_list1(space1, number)
+}
+
+/// `parser`, skipping any spaces or tabs before and after it.
+pub fn padded<'a, T>(
+    parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> impl FnMut(&'a str) -> IResult<'a, T> {
+    delimited(space0, parser, space0)
+}
+
+/// A `Label: values` line, such as `Time:      7  15   30`, returning the values.
+pub fn labelled<'a, T>(
+    label: &'static str,
+    values: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> impl FnMut(&'a str) -> IResult<'a, T> {
+    let mut values = padded(values);
+    move |input| {
+        let Some(rest) = input
+            .strip_prefix(label)
+            .and_then(|rest| rest.strip_prefix(':'))
+        else {
+            return Err(nom::Err::Error(NomError {
+                input,
+                expected: Cow::Owned(format!("`{label}:`")),
+            }));
+        };
+        values(rest)
+    }
+}
+
+/// The blank line between two parts of an input.
+pub fn blank_line(input: &str) -> IResult<'_, &str> {
+    context("a blank line", tag("\n\n"))(input)
+}
+
+/// One or more sections separated by blank lines, such as day 5's maps.
+pub fn sections<'a, T>(
+    section: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    separated_list1(blank_line, section)
+}
+
+/// A `key = (left, right)` record, such as a day 8 node, returning the key and the pair.
+pub fn record<'a, K, V>(
+    mut key: impl FnMut(&'a str) -> IResult<'a, K>,
+    mut value: impl FnMut(&'a str) -> IResult<'a, V>,
+) -> impl FnMut(&'a str) -> IResult<'a, (K, (V, V))> {
+    move |input| {
+        let (input, key) = key(input)?;
+        let (input, _) = context("` = `", tag(" = "))(input)?;
+        let (input, left) = preceded(context("`(`", tag("(")), &mut value)(input)?;
+        let (input, _) = context("`, `", tag(", "))(input)?;
+        let (input, right) = value(input)?;
+        let (input, _) = context("`)`", tag(")"))(input)?;
+        Ok((input, (key, (left, right))))
+    }
+}
+
+/// The rest of the input as rows of cells, one row per line, converting each character with
+/// `cell`. Characters that `cell` rejects are reported as `expected`, and every row must be as
+/// wide as the first.
+pub fn grid<'a, T>(
+    expected: &str,
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<Vec<T>>> {
+    let expected = expected.to_string();
+    move |input| {
+        let error = |input, expected| nom::Err::Error(NomError { input, expected });
+        let mut rows = Vec::new();
+        for line in input.lines() {
+            let row = line
+                .char_indices()
+                .map(|(offset, c)| {
+                    cell(c).ok_or_else(|| error(&line[offset..], Cow::Owned(expected.clone())))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let cols = rows.first().map_or(row.len(), Vec::len);
+            if row.len() != cols {
+                let offset = line
+                    .char_indices()
+                    .nth(cols)
+                    .map_or(line.len(), |(offset, _)| offset);
+                let expected = Cow::Owned(format!("a row of {cols} cells"));
+                return Err(error(&line[offset..], expected));
+            }
+            rows.push(row);
+        }
+        Ok((&input[input.len()..], rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{branch::alt, character::complete};
+
+    use super::*;
+
+    #[test]
+    fn test_finish() {
+        let source = "12 34";
+        assert_eq!(finish(source, complete::i32(source)).unwrap_err().column, 3);
+        assert_eq!(finish(source, complete::i32(&source[3..])), Ok(34));
+    }
+
+    #[test]
+    fn test_context() {
+        let source = "3 purple";
+        let mut colour = context("a colour", alt((tag("red"), tag("blue"))));
+        let error = finish(source, colour(&source[2..])).unwrap_err();
+        assert_eq!(error.expected, "a colour");
+        assert_eq!(error.column, 3);
+    }
+
+    #[test]
+    fn test_lines() {
+        let source = "1\n2\nx";
+        let error = lines(source, complete::i32).unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.expected, "a number");
+    }
+
+    #[test]
+    fn test_check_lines() {
+        let source = "header\n1\nx\n3\n4y";
+        let errors = check_lines(source, &source[7..], complete::i32).unwrap_err();
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.line, error.column))
+                .collect::<Vec<_>>(),
+            [(3, 1), (5, 2)]
+        );
+        assert_eq!(
+            check_lines(source, &source[7..8], complete::i32),
+            Ok(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        let source = "  1   22\t333 \t";
+        assert_eq!(
+            finish(source, padded(numbers(complete::u32))(source)),
+            Ok(vec![1, 22, 333])
+        );
+        // Without padding, the list stops before the separator it can't use.
+        assert_eq!(
+            numbers(complete::u32)("1  2  | 3"),
+            Ok(("  | 3", vec![1, 2]))
+        );
+
+        let source = "-3 +4  -0 -9223372036854775808";
+        assert_eq!(
+            finish(source, numbers(complete::i64)(source)),
+            Ok(vec![-3, 4, 0, i64::MIN])
+        );
+        let error = finish("1 -x", numbers(complete::i64)("1 -x")).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (2, "end of input"));
+    }
+
+    #[test]
+    fn test_labelled() {
+        let mut times = labelled("Time", numbers(complete::i64));
+        assert_eq!(times("Time:      7  15   30"), Ok(("", vec![7, 15, 30])));
+        assert_eq!(times("Time:7 -15"), Ok(("", vec![7, -15])));
+
+        let source = "Tim: 7";
+        let error = finish(source, times(source)).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (1, "`Time:`"));
+        let source = "Time: x";
+        let error = finish(source, times(source)).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (7, "a number"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let source = "1 2\n\n3\n\n4 5 6";
+        assert_eq!(
+            finish(source, sections(numbers(complete::u8))(source)),
+            Ok(vec![vec![1, 2], vec![3], vec![4, 5, 6]])
+        );
+        let source = "1 2\n3";
+        let error = finish(source, preceded(numbers(complete::u8), blank_line)(source));
+        assert_eq!(error.unwrap_err().expected, "a blank line");
+    }
+
+    #[test]
+    fn test_record() {
+        let mut node = record(complete::alphanumeric1, complete::alphanumeric1);
+        assert_eq!(node("AAA = (BBB, 11Z)"), Ok(("", ("AAA", ("BBB", "11Z")))));
+
+        let source = "AAA = (BBB,CCC)";
+        let error = finish(source, node(source)).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (11, "`, `"));
+        let source = "AAA=(BBB, CCC)";
+        let error = finish(source, node(source)).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (4, "` = `"));
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut digits = grid("a digit", |c| c.to_digit(10));
+        assert_eq!(digits("12\n34\n"), Ok(("", vec![vec![1, 2], vec![3, 4]])));
+        assert_eq!(digits(""), Ok(("", vec![])));
+
+        let source = "12\n3x";
+        let error = finish(source, digits(source)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "a digit");
+        let source = "12\n345";
+        let error = finish(source, digits(source)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.expected, "a row of 2 cells");
+    }
+}
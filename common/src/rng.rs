@@ -0,0 +1,92 @@
+use std::ops::RangeInclusive;
+
+/// A small seeded random number generator (SplitMix64). It's not suitable for anything
+/// security-related, but the same seed always gives the same sequence on every platform, which
+/// is what generating test inputs needs.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`. `n` must be positive.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        // Multiply-shift rather than `%`, which would favour small numbers.
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range");
+        let span = end.abs_diff(start);
+        if span == u64::MAX {
+            return self.next_u64() as i64;
+        }
+        start.wrapping_add(self.below(span + 1) as i64)
+    }
+
+    /// An index into a collection of `len` items.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(1), numbers(1));
+        assert_ne!(numbers(1), numbers(2));
+        // SplitMix64's published output for seed 0.
+        assert_eq!(numbers(0)[0], 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert_eq!(rng.range(5..=5), 5);
+        }
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            seen[(rng.range(-3..=3) + 3) as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    env::args,
+    fmt,
+    path::Path,
+    process::ExitCode,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alloc::{self, Allocations},
+    bytes::Lines,
+    differential::Check,
+    input,
+    rng::Rng,
+    Answer, Extras, ParseError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part `{s}`, expected 1 or 2")),
+        }
+    }
+}
+
+/// A day's puzzle. The input is parsed once and both parts are solved from the parsed form, which
+/// may borrow from the input text.
+pub trait Solution {
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError>;
+
+    fn part1(input: &Self::Input<'_>) -> Answer;
+
+    fn part2(input: &Self::Input<'_>) -> Answer;
+
+    /// Named values worth reporting alongside a part's answer, like where it was found. Most days
+    /// have none.
+    fn extras(_input: &Self::Input<'_>, _part: Part) -> Extras {
+        Vec::new()
+    }
+
+    /// Everything wrong with `input`, found without solving it: syntax errors, and problems the
+    /// parser lets through but the solvers would trip over, like references to things that
+    /// aren't there. Unlike [`Solution::parse`], it should keep going after the first problem
+    /// where it can. By default it only runs the parser.
+    fn check(input: &str) -> Vec<ParseError> {
+        Self::parse(input).err().into_iter().collect()
+    }
+
+    /// A problem with a parsed input that only `part` trips over, like a missing start that the
+    /// other part doesn't walk from, so inputs meant for the other part alone still parse. It's
+    /// checked before solving `part`. `text` is what `input` was parsed from, to point into. By
+    /// default there are none.
+    fn check_part(_text: &str, _input: &Self::Input<'_>, _part: Part) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    /// Slow reference implementations to compare the fast ones against, on generated inputs (see
+    /// [`crate::differential`]). Most days have none.
+    fn differentials() -> Vec<Box<dyn Check>> {
+        Vec::new()
+    }
+
+    /// A random but valid puzzle input, roughly `size` lines or rows long, for stress testing.
+    /// The same `rng` seed must always give the same input.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+
+    /// A synthetic input shaped like `input`, with the same number of lines, grid size, value
+    /// ranges and so on but fresh random content, that can be published in place of a real
+    /// puzzle input. The same `rng` seed must always give the same input.
+    fn lookalike(_input: &Self::Input<'_>, _rng: &mut Rng) -> Option<String> {
+        None
+    }
+
+    /// Solves `part` a line at a time straight from the input's bytes (see [`crate::bytes`]),
+    /// for inputs too big to read into memory and parse whole, or `None` if the day needs all of
+    /// its input at once. Only what the answer needs should be kept from each line, so memory
+    /// doesn't grow with the input, and the answers must be the same as [`Solution::part1`] and
+    /// [`Solution::part2`] give.
+    fn stream(_part: Part, _lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        None
+    }
+}
+
+/// Parses `text` and runs [`Solution::check_part`] on it for `part`.
+fn parse_for<S: Solution>(part: Part, text: &str) -> Result<S::Input<'_>, ParseError> {
+    let input = S::parse(text)?;
+    S::check_part(text, &input, part)?;
+    Ok(input)
+}
+
+/// Normalizes `input` (see [`input::normalize`]), parses it and solves one part.
+pub fn solve<S: Solution>(part: Part, input: &str) -> Result<Answer, ParseError> {
+    let text = input::normalize(input).text;
+    let input = parse_for::<S>(part, &text)?;
+    Ok(match part {
+        Part::One => S::part1(&input),
+        Part::Two => S::part2(&input),
+    })
+}
+
+/// How long the parse and solve steps of one run took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Like [`solve`], but also times the parse and solve steps separately. Normalizing the input
+/// isn't counted in either.
+pub fn solve_timed<S: Solution>(part: Part, input: &str) -> Result<(Answer, Timings), ParseError> {
+    let text = input::normalize(input).text;
+
+    let start = Instant::now();
+    let input = parse_for::<S>(part, &text)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let answer = match part {
+        Part::One => S::part1(&input),
+        Part::Two => S::part2(&input),
+    };
+    let solve = start.elapsed();
+
+    Ok((answer, Timings { parse, solve }))
+}
+
+/// Parses `input` and collects the [`Solution::extras`] for one part.
+pub fn solve_extras<S: Solution>(part: Part, input: &str) -> Result<Extras, ParseError> {
+    let text = input::normalize(input).text;
+    let input = parse_for::<S>(part, &text)?;
+    Ok(S::extras(&input, part))
+}
+
+/// Normalizes and parses `input`, and makes a [`Solution::lookalike`] of it.
+pub fn lookalike_input<S: Solution>(
+    input: &str,
+    rng: &mut Rng,
+) -> Result<Option<String>, ParseError> {
+    let text = input::normalize(input).text;
+    let input = S::parse(&text)?;
+    Ok(S::lookalike(&input, rng))
+}
+
+/// Normalizes `input` and runs [`Solution::check`] on it, returning the problems in the order they
+/// appear. Given a `part`, a valid input is also run through [`Solution::check_part`] for it.
+pub fn check_input<S: Solution>(input: &str, part: Option<Part>) -> Vec<ParseError> {
+    let text = input::normalize(input).text;
+    let mut problems = S::check(&text);
+    if let (true, Some(part)) = (problems.is_empty(), part) {
+        problems.extend(parse_for::<S>(part, &text).err());
+    }
+    problems.sort_by_key(|problem| (problem.line, problem.column));
+    problems
+}
+
+/// What the parse and solve steps of one run allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StepAllocations {
+    pub parse: Allocations,
+    pub solve: Allocations,
+}
+
+/// Like [`solve`], but also counts what the parse and solve steps allocate. The counts are only
+/// non-zero if [`alloc::CountingAllocator`] is installed. Normalizing the input isn't counted.
+pub fn solve_counted<S: Solution>(
+    part: Part,
+    input: &str,
+) -> Result<(Answer, StepAllocations), ParseError> {
+    let text = input::normalize(input).text;
+
+    let (input, parse) = alloc::measure(|| parse_for::<S>(part, &text));
+    let input = input?;
+    let (answer, solve) = alloc::measure(|| match part {
+        Part::One => S::part1(&input),
+        Part::Two => S::part2(&input),
+    });
+
+    Ok((answer, StepAllocations { parse, solve }))
+}
+
+/// Prints every problem [`check_input`] finds in `input` for `part`, followed by a count, and
+/// reports whether there were none.
+pub fn print_problems<S: Solution>(source: &impl fmt::Display, input: &str, part: Part) -> bool {
+    let problems = check_input::<S>(input, Some(part));
+    for problem in &problems {
+        println!("{source}: {problem}");
+    }
+    match problems.len() {
+        0 => println!("{source}: ok"),
+        1 => println!("{source}: 1 problem"),
+        count => println!("{source}: {count} problems"),
+    }
+    problems.is_empty()
+}
+
+/// Entry point for a day's `partN` binaries: solves every input named by the first argument (see
+/// [`input::SOURCES_HELP`]) and prints the answers, or a usage message. With `--check`, the
+/// inputs are checked instead of solved.
+pub fn main<S: Solution>(part: Part) -> ExitCode {
+    let mut args = args().collect::<Vec<_>>();
+    let program = args
+        .first()
+        .and_then(|program| Path::new(program).file_name())
+        .map_or("part".into(), |name| name.to_string_lossy().into_owned());
+    let check = args
+        .iter()
+        .position(|arg| arg == "--check")
+        .map(|i| args.remove(i));
+    let sources = match args.as_slice() {
+        [_] => input::sources(None),
+        [_, arg] => input::sources(Some(arg)),
+        _ => Err("too many arguments".to_string()),
+    };
+    let sources = match sources {
+        Ok(sources) => sources,
+        Err(message) => {
+            eprintln!(
+                "error: {message}\n\nusage: {program} [--check] [input]\n\n{}",
+                input::SOURCES_HELP
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut code = ExitCode::SUCCESS;
+    if check.is_some() {
+        for source in &sources {
+            let valid = source
+                .read()
+                .map(|input| print_problems::<S>(source, &input.text, part));
+            match valid {
+                Ok(true) => {}
+                Ok(false) => code = ExitCode::FAILURE,
+                Err(message) => {
+                    eprintln!("error: {message}");
+                    code = ExitCode::FAILURE;
+                }
+            }
+        }
+        return code;
+    }
+    for source in &sources {
+        let answer = source.read().and_then(|input| {
+            for change in &input.changes {
+                eprintln!("note: {source}: {change}");
+            }
+            solve::<S>(part, &input.text).map_err(|err| format!("failed to parse {source}: {err}"))
+        });
+        match answer {
+            Ok(answer) if sources.len() == 1 => println!("{answer}"),
+            Ok(answer) => println!("{source}: {answer}"),
+            Err(message) => {
+                eprintln!("error: {message}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+    code
+}
@@ -0,0 +1,180 @@
+//! Tracing: solutions record named events about how they reached an answer, like which numbers
+//! counted as part numbers or which map entry a span went through. Nothing is recorded unless a
+//! caller asks for it with [`capture`], so tracing is silent by default and an event costs one
+//! thread-local check when it's off.
+//!
+//! Events are collected per thread, so a solution that spreads its work over threads only traces
+//! what happens on the calling one.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    str::FromStr,
+};
+
+use crate::{Extras, Value};
+
+/// How much detail an event gives. Capturing at one level also captures every level above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// The steps that explain an answer, a handful per line of input.
+    Info,
+    /// Every intermediate step, which can run to many thousands of events.
+    Debug,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            _ => Err(format!("invalid level `{s}`, expected info or debug")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub level: Level,
+    pub name: &'static str,
+    pub fields: Extras,
+}
+
+impl Event {
+    /// The event as one JSON object, with its name and level ahead of its fields.
+    pub fn to_value(&self) -> Value {
+        let mut fields = vec![
+            ("event", self.name.into()),
+            ("level", self.level.to_string().into()),
+        ];
+        fields.extend(self.fields.iter().cloned());
+        Value::Object(fields)
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (name, value) in &self.fields {
+            write!(f, " {name}={}", value.to_json())?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static LEVEL: Cell<Option<Level>> = const { Cell::new(None) };
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Whether events at `level` are being recorded. Only worth checking before doing extra work
+/// that's just for tracing, since [`event`] already skips building its fields when it's off.
+pub fn enabled(level: Level) -> bool {
+    LEVEL.get().is_some_and(|max| level <= max)
+}
+
+/// Records an event if `level` is being captured. `fields` is only called if it is.
+pub fn event(level: Level, name: &'static str, fields: impl FnOnce() -> Extras) {
+    if enabled(level) {
+        let event = Event {
+            level,
+            name,
+            fields: fields(),
+        };
+        EVENTS.with_borrow_mut(|events| events.push(event));
+    }
+}
+
+pub fn info(name: &'static str, fields: impl FnOnce() -> Extras) {
+    event(Level::Info, name, fields);
+}
+
+pub fn debug(name: &'static str, fields: impl FnOnce() -> Extras) {
+    event(Level::Debug, name, fields);
+}
+
+/// Puts back the level and events from before a [`capture`], even if it panics.
+struct Restore {
+    level: Option<Level>,
+    events: Vec<Event>,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        LEVEL.set(self.level);
+        EVENTS.set(std::mem::take(&mut self.events));
+    }
+}
+
+/// Runs `f`, returning its result along with the events it recorded at `level` or above.
+/// Captures can be nested; the inner one gets its own events.
+pub fn capture<T>(level: Level, f: impl FnOnce() -> T) -> (T, Vec<Event>) {
+    let _restore = Restore {
+        level: LEVEL.replace(Some(level)),
+        events: EVENTS.take(),
+    };
+    let result = f();
+    (result, EVENTS.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve() -> u32 {
+        info("start", Vec::new);
+        for i in 1..=2 {
+            debug("step", || vec![("i", i.into())]);
+        }
+        3
+    }
+
+    #[test]
+    fn test_silent_by_default() {
+        assert!(!enabled(Level::Info));
+        solve();
+        let ((), events) = capture(Level::Info, || {});
+        assert_eq!(events, vec![]);
+    }
+
+    #[test]
+    fn test_capture_levels() {
+        let (answer, events) = capture(Level::Info, solve);
+        assert_eq!(answer, 3);
+        assert_eq!(events.iter().map(|e| e.name).collect::<Vec<_>>(), ["start"]);
+
+        let (_, events) = capture(Level::Debug, solve);
+        assert_eq!(
+            events.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["start", "step i=1", "step i=2"]
+        );
+        assert_eq!(
+            events[1].to_value().to_json(),
+            r#"{"event":"step","level":"debug","i":1}"#
+        );
+    }
+
+    #[test]
+    fn test_nested_captures() {
+        let (inner, outer) = capture(Level::Debug, || {
+            info("outer", Vec::new);
+            let (_, inner) = capture(Level::Info, solve);
+            debug("outer", Vec::new);
+            inner
+        });
+        assert_eq!(inner.len(), 1);
+        assert_eq!(outer.len(), 2);
+        assert!(!enabled(Level::Info));
+    }
+}
@@ -0,0 +1,323 @@
+use std::fmt::Write;
+
+/// Extra data a solution reports alongside an answer (see [`crate::Solution::extras`]), shaped
+/// so that it can be written out as JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Int(i128),
+    Text(String),
+    List(Vec<Value>),
+    Object(Vec<(&'static str, Value)>),
+}
+
+/// Named extras, in the order they should be reported.
+pub type Extras = Vec<(&'static str, Value)>;
+
+impl Value {
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        match self {
+            Value::Int(n) => write!(json, "{n}").unwrap(),
+            Value::Text(text) => write_json_string(json, text),
+            Value::List(values) => {
+                json.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        json.push(',');
+                    }
+                    value.write_json(json);
+                }
+                json.push(']');
+            }
+            Value::Object(fields) => {
+                json.push('{');
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        json.push(',');
+                    }
+                    write_json_string(json, name);
+                    json.push(':');
+                    value.write_json(json);
+                }
+                json.push('}');
+            }
+        }
+    }
+}
+
+fn write_json_string(json: &mut String, text: &str) {
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+macro_rules! impl_from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Value {
+                fn from(value: $int) -> Self {
+                    Value::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// JSON read back in, such as [`Value::to_json`] writes. Its object keys are owned, unlike a
+/// [`Value::Object`]'s, so it's a type of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Int(i128),
+    Text(String),
+    List(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Reads the integers, strings, lists and objects that [`Value::to_json`] writes.
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut reader = Reader { text, pos: 0 };
+        let json = reader.value()?;
+        reader.spaces();
+        match reader.rest().is_empty() {
+            true => Ok(json),
+            false => Err(reader.error("the end of the JSON")),
+        }
+    }
+
+    /// The field called `name`, if this is an object with one.
+    pub fn get(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn error(&self, expected: &str) -> String {
+        format!("expected {expected} at byte {}", self.pos)
+    }
+
+    fn spaces(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.spaces();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.error(&format!("`{token}`"))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.spaces();
+        match self.rest().chars().next() {
+            Some('"') => self.string().map(Json::Text),
+            Some('[') => {
+                self.pos += 1;
+                self.items("]", Self::value).map(Json::List)
+            }
+            Some('{') => {
+                self.pos += 1;
+                let field = |reader: &mut Self| {
+                    reader.spaces();
+                    let name = reader.string()?;
+                    reader.expect(":")?;
+                    Ok((name, reader.value()?))
+                };
+                self.items("}", field).map(Json::Object)
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let rest = self.rest();
+                let len = rest[1..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(rest.len(), |i| i + 1);
+                let number = rest[..len].parse().map_err(|_| self.error("an integer"))?;
+                self.pos += len;
+                Ok(Json::Int(number))
+            }
+            _ => Err(self.error("a value")),
+        }
+    }
+
+    /// The items of a list or object, after its opening bracket, up to and including `end`.
+    fn items<T>(
+        &mut self,
+        end: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        if self.eat(end) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat(end) {
+                return Ok(items);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.rest().starts_with('"') {
+            return Err(self.error("a string"));
+        }
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            let c = match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('/') => '/',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('u') => {
+                        let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("a `\\u` escape of a character"))?
+                    }
+                    _ => return Err(self.error("a string with valid escapes")),
+                },
+                c => c,
+            };
+            text.push(c);
+        }
+        Err(self.error("a string with a closing `\"`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let value = Value::Object(vec![
+            (
+                "farthest",
+                Value::Object(vec![("row", 3.into()), ("col", 4.into())]),
+            ),
+            ("loop_sizes", vec![2usize, 3].into()),
+            ("note", "a \"quoted\"\n\u{1}line".into()),
+            ("empty", Value::List(vec![])),
+        ]);
+        assert_eq!(
+            value.to_json(),
+            r#"{"farthest":{"row":3,"col":4},"loop_sizes":[2,3],"note":"a \"quoted\"\n\u0001line","empty":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let value = Value::Object(vec![
+            (
+                "answer",
+                (-170141183460469231731687303715884105728i128).into(),
+            ),
+            ("error", "a \"quoted\"\n\u{1}line ✓".into()),
+            ("list", Value::List(vec![1.into(), Value::List(vec![])])),
+        ]);
+        let json = Json::parse(&value.to_json()).unwrap();
+        assert_eq!(json.get("answer"), Some(&Json::Int(i128::MIN)));
+        assert_eq!(
+            json.get("error"),
+            Some(&Json::Text("a \"quoted\"\n\u{1}line ✓".to_string()))
+        );
+        assert_eq!(
+            json.get("list"),
+            Some(&Json::List(vec![Json::Int(1), Json::List(vec![])]))
+        );
+        assert_eq!(json.get("missing"), None);
+        assert_eq!(
+            Json::parse(" { \"a\" : [ 1 , 2 ] } "),
+            Ok(Json::Object(vec![(
+                "a".to_string(),
+                Json::List(vec![Json::Int(1), Json::Int(2)])
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_json_errors() {
+        assert_eq!(
+            Json::parse("1.5"),
+            Err("expected the end of the JSON at byte 1".into())
+        );
+        assert_eq!(Json::parse("[1 2]"), Err("expected `,` at byte 3".into()));
+        assert_eq!(
+            Json::parse("{1: 2}"),
+            Err("expected a string at byte 1".into())
+        );
+        assert!(Json::parse("\"open").is_err());
+        assert!(Json::parse("true").is_err());
+    }
+}
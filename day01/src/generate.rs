@@ -0,0 +1,70 @@
+use common::rng::Rng;
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+/// Spelled digits sharing a letter, where reading left to right and right to left disagree.
+const OVERLAPS: [&str; 8] = [
+    "oneight",
+    "twone",
+    "threeight",
+    "fiveight",
+    "sevenine",
+    "eightwo",
+    "eighthree",
+    "nineight",
+];
+
+/// A digit, a spelled digit, a pair of overlapping ones or a few letters.
+fn piece(rng: &mut Rng) -> String {
+    match rng.below(4) {
+        0 => char::from(b'1' + rng.below(9) as u8).to_string(),
+        1 => rng.choose(&WORDS).to_string(),
+        2 => rng.choose(&OVERLAPS).to_string(),
+        _ => (0..rng.range(1..=4))
+            .map(|_| char::from(b'a' + rng.below(26) as u8))
+            .collect(),
+    }
+}
+
+/// Adds a digit somewhere in `line` if it doesn't have one, so part 1 has something to read.
+fn ensure_digit(rng: &mut Rng, line: &mut String) {
+    if !line.contains(|c: char| c.is_ascii_digit()) {
+        let at = line.len() * rng.index(2);
+        line.insert(at, char::from(b'1' + rng.below(9) as u8));
+    }
+}
+
+/// Calibration lines of letters, digits and spelled digits, including overlapping ones. Every
+/// line has at least one digit, so part 1 has something to read.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let mut line = (0..rng.range(1..=8))
+                .map(|_| piece(rng))
+                .collect::<String>();
+            ensure_digit(rng, &mut line);
+            line + "\n"
+        })
+        .collect()
+}
+
+/// Fresh lines as long as each of `lines`.
+pub fn lookalike(lines: &[&str], rng: &mut Rng) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let length = line.len().max(1);
+            let mut new = String::new();
+            while new.len() < length {
+                new += &piece(rng);
+            }
+            new.truncate(length);
+            if !new.contains(|c: char| c.is_ascii_digit()) {
+                new.pop();
+                ensure_digit(rng, &mut new);
+            }
+            new + "\n"
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+use common::{bytes::Lines, rng::Rng, Answer, ParseError, Part, Solution};
+
+mod generate;
+mod stream;
+
+/// A calibration line's first and last digits, as part 1 reads them and as part 2 does.
+pub struct Line<'a> {
+    text: &'a str,
+    /// `None` if the line only has spelled-out digits.
+    digits: Option<(u32, u32)>,
+    spelled: (u32, u32),
+}
+
+impl Line<'_> {
+    fn value((first, last): (u32, u32)) -> u64 {
+        u64::from(first * 10 + last)
+    }
+}
+
+/// Every digit in `line` and where it is, counting spelled-out ones if `written`.
+fn parse_digit_positions(line: &str, written: bool) -> Vec<(usize, u32)> {
+    const WRITTEN_DIGITS: &[&str] = &[
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+
+    let digits = line
+        .char_indices()
+        .filter(|(_, c)| c.is_ascii_digit())
+        .map(|(i, c)| (i, c.to_digit(10).unwrap()));
+    let written = WRITTEN_DIGITS
+        .iter()
+        .enumerate()
+        .filter(|_| written)
+        .flat_map(|(i, dig)| {
+            line.match_indices(dig)
+                .map(move |(m, _)| (m, u32::try_from(i + 1).unwrap()))
+        });
+
+    let mut vec: Vec<_> = written.chain(digits).collect();
+    vec.sort_by_key(|(i, _)| *i);
+    vec
+}
+
+/// The first and last digits in `line`, counting spelled-out ones if `written`.
+fn first_and_last(line: &str, written: bool) -> Option<(u32, u32)> {
+    let positions = parse_digit_positions(line, written);
+    Some((positions.first()?.1, positions.last()?.1))
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input<'a> = Vec<Line<'a>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        input
+            .lines()
+            .map(|text| {
+                let spelled = first_and_last(text, true).ok_or_else(|| {
+                    ParseError::new(input, text, "a digit or a spelled-out digit")
+                })?;
+                Ok(Line {
+                    text,
+                    digits: first_and_last(text, false),
+                    spelled,
+                })
+            })
+            .collect()
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        input
+            .lines()
+            .filter(|line| first_and_last(line, true).is_none())
+            .map(|line| ParseError::new(input, line, "a digit or a spelled-out digit"))
+            .collect()
+    }
+
+    fn check_part(text: &str, lines: &Self::Input<'_>, part: Part) -> Result<(), ParseError> {
+        match lines.iter().find(|line| line.digits.is_none()) {
+            Some(line) if part == Part::One => Err(ParseError::new(text, line.text, "a digit")),
+            _ => Ok(()),
+        }
+    }
+
+    fn part1(lines: &Self::Input<'_>) -> Answer {
+        lines
+            .iter()
+            .map(|line| Line::value(line.digits.expect("checked before solving")))
+            .sum::<u64>()
+            .into()
+    }
+
+    fn part2(lines: &Self::Input<'_>) -> Answer {
+        lines
+            .iter()
+            .map(|line| Line::value(line.spelled))
+            .sum::<u64>()
+            .into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(lines: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        let lines = lines.iter().map(|line| line.text).collect::<Vec<_>>();
+        Some(generate::lookalike(&lines, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::solve;
+
+    use super::*;
+
+    #[test]
+    fn test_lines_without_digits_are_parse_errors() {
+        let spelled = "1abc2\ntwo1nine\neightwothree";
+        assert_eq!(
+            solve::<Day01>(Part::Two, spelled),
+            Ok(Answer::from(12 + 29 + 83))
+        );
+        for (part, input, expected) in [
+            (Part::One, spelled, "a digit"),
+            (Part::Two, "1abc2\nxyz", "a digit or a spelled-out digit"),
+        ] {
+            let error = solve::<Day01>(part, input).unwrap_err();
+            let streamed = Day01::stream(part, &mut Lines::from_bytes(input))
+                .unwrap()
+                .unwrap_err();
+            assert_eq!((error.line, error.column), (streamed.line, streamed.column));
+            assert_eq!(error.expected, expected);
+            assert_eq!(streamed.expected, expected);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Solving a line at a time over bytes, for inputs too big to read in whole.
+
+use common::{bytes::Lines, Answer, ParseError, Part};
+
+const WRITTEN_DIGITS: [&[u8]; 9] = [
+    b"one", b"two", b"three", b"four", b"five", b"six", b"seven", b"eight", b"nine",
+];
+
+/// The digit `rest` starts with, counting spelled-out ones if `written`.
+fn digit_at(rest: &[u8], written: bool) -> Option<u32> {
+    match *rest.first()? {
+        b @ b'0'..=b'9' => Some(u32::from(b - b'0')),
+        _ if written => (1..)
+            .zip(WRITTEN_DIGITS)
+            .find(|(_, digit)| rest.starts_with(digit))
+            .map(|(value, _)| value),
+        _ => None,
+    }
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let written = part == Part::Two;
+    let mut sum = 0u64;
+    while let Some((number, line)) = lines.next_line() {
+        let first = (0..line.len()).find_map(|i| digit_at(&line[i..], written));
+        let last = (0..line.len())
+            .rev()
+            .find_map(|i| digit_at(&line[i..], written));
+        let (Some(first), Some(last)) = (first, last) else {
+            let expected = match written {
+                true => "a digit or a spelled-out digit",
+                false => "a digit",
+            };
+            return Err(ParseError::in_line(number, line, 0, expected));
+        };
+        sum += u64::from(first * 10 + last);
+    }
+    Ok(sum.into())
+}
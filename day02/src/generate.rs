@@ -0,0 +1,56 @@
+use common::{hash::HashMap, rng::Rng};
+use itertools::Itertools;
+
+use crate::Game;
+
+/// A draw of `colors` different colours, in any order, with `count` of each.
+fn hand(rng: &mut Rng, colors: usize, mut count: impl FnMut(&mut Rng, &str) -> u32) -> String {
+    let mut all = ["red", "green", "blue"];
+    rng.shuffle(&mut all);
+    all[..colors.min(3)]
+        .iter()
+        .map(|color| format!("{} {color}", count(rng, color)))
+        .join(", ")
+}
+
+/// Games of a few draws each, with any mix of colours and counts, some of them over part 1's
+/// limits and some games never showing one of the colours.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (1..=size)
+        .map(|id| {
+            let hands = (0..rng.range(1..=6)).map(|_| {
+                let colors = rng.range(1..=3) as usize;
+                hand(rng, colors, |rng, _| rng.range(1..=20) as u32)
+            });
+            format!("Game {id}: {}\n", hands.collect_vec().join("; "))
+        })
+        .collect()
+}
+
+/// The same games with as many draws each, and as many colours in each draw, but fresh colours
+/// and counts. Each colour's counts are drawn from that colour's counts in `games`.
+pub fn lookalike(games: &[Game], rng: &mut Rng) -> String {
+    let mut counts = HashMap::<&str, Vec<u32>>::default();
+    for (color, &count) in games
+        .iter()
+        .flat_map(|game| &game.hands)
+        .flat_map(|hand| &hand.cubes)
+    {
+        counts.entry(color).or_default().push(count);
+    }
+    let mut count = |rng: &mut Rng, color: &str| match counts.get(color) {
+        Some(counts) => *rng.choose(counts),
+        None => rng.range(1..=20) as u32,
+    };
+
+    games
+        .iter()
+        .map(|game| {
+            let hands = game
+                .hands
+                .iter()
+                .map(|draw| hand(rng, draw.cubes.len(), &mut count));
+            format!("Game {}: {}\n", game.id, hands.collect_vec().join("; "))
+        })
+        .collect()
+}
@@ -1,13 +1,23 @@
-use std::collections::HashMap;
-
+use common::{
+    bytes::Lines,
+    hash::{HashMap, HashSet},
+    parse::{self, finish, IResult},
+    rng::Rng,
+    Answer, ParseError, Part, Solution,
+};
+use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{digit1, newline},
+    character::complete::{self, newline},
+    combinator::cut,
+    error::context,
     multi::separated_list1,
-    IResult,
 };
 
+mod generate;
+mod stream;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Hand {
     pub cubes: HashMap<String, u32>,
@@ -19,15 +29,18 @@ pub struct Game {
     pub hands: Vec<Hand>,
 }
 
-fn parse_cube(input: &str) -> IResult<&str, (String, u32)> {
-    let (input, count) = digit1(input)?;
+fn parse_cube(input: &str) -> IResult<'_, (String, u32)> {
+    let (input, count) = complete::u32(input)?;
     let (input, _) = tag(" ")(input)?;
-    let (input, color) = alt((tag("red"), tag("green"), tag("blue")))(input)?;
-    Ok((input, (color.to_string(), count.parse().unwrap())))
+    let (input, color) = context(
+        "`red`, `green` or `blue`",
+        alt((tag("red"), tag("green"), tag("blue"))),
+    )(input)?;
+    Ok((input, (color.to_string(), count)))
 }
 
-fn parse_hand(input: &str) -> IResult<&str, Hand> {
-    let (input, cubes) = separated_list1(tag(", "), parse_cube)(input)?;
+fn parse_hand(input: &str) -> IResult<'_, Hand> {
+    let (input, cubes) = separated_list1(tag(", "), cut(parse_cube))(input)?;
     Ok((
         input,
         Hand {
@@ -36,25 +49,106 @@ fn parse_hand(input: &str) -> IResult<&str, Hand> {
     ))
 }
 
-fn parse_game(input: &str) -> IResult<&str, Game> {
-    let (input, _) = tag("Game ")(input)?;
-    let (input, id) = digit1(input)?;
-    let (input, _) = tag(": ")(input)?;
-    let (input, hands) = separated_list1(tag("; "), parse_hand)(input)?;
-    Ok((
-        input,
-        Game {
-            id: id.parse().unwrap(),
-            hands,
-        },
-    ))
+fn parse_game(input: &str) -> IResult<'_, Game> {
+    let (input, _) = context("`Game `", tag("Game "))(input)?;
+    let (input, id) = complete::u32(input)?;
+    let (input, _) = context("`: `", tag(": "))(input)?;
+    let (input, hands) = separated_list1(tag("; "), cut(parse_hand))(input)?;
+    Ok((input, Game { id, hands }))
 }
 
-pub fn parse(input: &str) -> IResult<&str, Vec<Game>> {
-    let (input, games) = separated_list1(newline, parse_game)(input)?;
+pub fn parse(input: &str) -> IResult<'_, Vec<Game>> {
+    let (input, games) = separated_list1(newline, cut(parse_game))(input)?;
     Ok((input, games))
 }
 
+fn is_possible(max_cubes: &HashMap<&str, u32>, game: &Game) -> bool {
+    game.hands.iter().all(|hand| {
+        hand.cubes
+            .iter()
+            .all(|(color, count)| count <= max_cubes.get(color.as_str()).unwrap())
+    })
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input<'a> = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        finish(input, parse(input))
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        if let Err(errors) = parse::check_lines(input, input, parse_game) {
+            return errors;
+        }
+
+        // A hand is parsed into a map, which would quietly keep only one count of a colour.
+        let mut problems = Vec::new();
+        for line in input.lines() {
+            let (_, hands) = line.split_once(": ").unwrap();
+            for hand in hands.split("; ") {
+                let mut colours = HashSet::default();
+                for cube in hand.split(", ") {
+                    let (_, colour) = cube.split_once(' ').unwrap();
+                    if !colours.insert(colour) {
+                        let expected = format!("`{colour}` only once in a hand");
+                        problems.push(ParseError::new(input, cube, expected));
+                    }
+                }
+            }
+        }
+        problems
+    }
+
+    fn part1(games: &Self::Input<'_>) -> Answer {
+        let max_cubes: HashMap<&str, u32> = vec![("red", 12), ("green", 13), ("blue", 14)]
+            .into_iter()
+            .collect();
+
+        games
+            .iter()
+            .filter(|g| is_possible(&max_cubes, g))
+            .map(|g| u64::from(g.id))
+            .sum::<u64>()
+            .into()
+    }
+
+    fn part2(games: &Self::Input<'_>) -> Answer {
+        games
+            .iter()
+            .map(|game| {
+                let cube_counts = game
+                    .hands
+                    .iter()
+                    .flat_map(|hand| &hand.cubes)
+                    .into_group_map()
+                    .into_values()
+                    .map(|counts| u64::from(*counts.into_iter().max().unwrap()))
+                    .collect_vec();
+                match cube_counts.len() {
+                    3 => cube_counts.iter().product(),
+                    _ => 0,
+                }
+            })
+            .sum::<u64>()
+            .into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(games: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(games, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +173,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_error() {
+        let input = "Game 1: 3 blue\nGame 2: 1 red, 8 purple";
+        let error = Day02::parse(input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 18));
+        assert_eq!(error.expected, "`red`, `green` or `blue`");
+    }
+
     #[test]
     fn test_parse_game() {
         assert_eq!(
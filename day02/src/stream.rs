@@ -0,0 +1,53 @@
+//! Solving a line at a time over bytes, for inputs too big to read in whole.
+
+use common::{
+    bytes::{Cursor, Lines},
+    Answer, ParseError, Part,
+};
+
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+const MAX_CUBES: [u64; 3] = [12, 13, 14];
+
+/// A game's id and the most cubes of each colour shown at once, if any were.
+fn parse_game(cursor: &mut Cursor) -> Result<(u64, [Option<u64>; 3]), ParseError> {
+    cursor.expect("Game ")?;
+    let id = cursor.unsigned()?;
+    cursor.expect(": ")?;
+    let mut most = [None; 3];
+    loop {
+        let count = cursor.unsigned()?;
+        cursor.expect(" ")?;
+        let colour = COLOURS
+            .iter()
+            .position(|colour| cursor.eat(colour))
+            .ok_or_else(|| cursor.error("`red`, `green` or `blue`"))?;
+        most[colour] = most[colour].max(Some(count));
+        if !cursor.eat(", ") && !cursor.eat("; ") {
+            break;
+        }
+    }
+    cursor.end()?;
+    Ok((id, most))
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let mut sum = 0u64;
+    while let Some((number, line)) = lines.next_line() {
+        let (id, most) = parse_game(&mut Cursor::new(number, line))?;
+        sum += match part {
+            Part::One => {
+                let possible = most
+                    .iter()
+                    .zip(MAX_CUBES)
+                    .all(|(count, max)| count.is_none_or(|count| count <= max));
+                if possible {
+                    id
+                } else {
+                    0
+                }
+            }
+            Part::Two => most.iter().map(|count| count.unwrap_or(0)).product(),
+        };
+    }
+    Ok(sum.into())
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use common::rng::Rng;
+
+use crate::{Element, Schematic};
+
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'];
+
+/// How a schematic's cells are filled in.
+struct Style<'a> {
+    symbols: &'a [char],
+    /// Chance of a number starting on a cell that doesn't follow one.
+    number_chance: f64,
+    /// The same, for the first cell of a row and the last few.
+    edge_number_chance: f64,
+    /// Chance of a symbol on a cell that doesn't get a number.
+    symbol_chance: f64,
+    max_digits: u32,
+}
+
+fn draw(rng: &mut Rng, rows: usize, cols: usize, style: &Style) -> String {
+    let mut text = String::new();
+    for _ in 0..rows {
+        let mut row = String::new();
+        while row.len() < cols {
+            let left = cols - row.len();
+            let at_edge = row.is_empty() || left <= style.max_digits as usize;
+            let last_is_digit = row.ends_with(|c: char| c.is_ascii_digit());
+            let number_chance = match at_edge {
+                true => style.edge_number_chance,
+                false => style.number_chance,
+            };
+            if !last_is_digit && rng.chance(number_chance) {
+                let digits = rng.range(1..=(style.max_digits as i64).min(left as i64)) as u32;
+                let number = rng.range(10i64.pow(digits - 1)..=10i64.pow(digits) - 1);
+                row += &number.to_string();
+            } else if rng.chance(style.symbol_chance) {
+                row.push(*rng.choose(style.symbols));
+            } else {
+                row.push('.');
+            }
+        }
+        text += &row;
+        text.push('\n');
+    }
+    text
+}
+
+/// A `size` x `size` schematic of numbers and symbols. Numbers are often placed against the left
+/// and right edges, where neighbour lookups are easiest to get wrong.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let style = Style {
+        symbols: &SYMBOLS,
+        number_chance: 0.2,
+        edge_number_chance: 0.5,
+        symbol_chance: 0.15,
+        max_digits: 3,
+    };
+    draw(rng, size, size, &style)
+}
+
+/// A schematic the size of `schematic`, using the same symbols, with about as many numbers and
+/// symbols and no longer numbers.
+pub fn lookalike(schematic: &Schematic, rng: &mut Rng) -> String {
+    let mut symbols = BTreeSet::new();
+    let (mut numbers, mut digits, mut max_digits) = (0, 0, 1);
+    for element in &schematic.elements {
+        match element {
+            Element::Number(number) => {
+                numbers += 1;
+                digits += number.positions.len();
+                max_digits = max_digits.max(number.positions.len() as u32);
+            }
+            Element::Symbol(symbol) => {
+                symbols.insert(symbol.char);
+            }
+        }
+    }
+    let symbol_count = schematic.elements.len() - numbers;
+    let symbols = match symbols.is_empty() {
+        true => SYMBOLS.to_vec(),
+        false => symbols.into_iter().collect(),
+    };
+
+    let (rows, cols) = schematic.grid.size();
+    let free_cells = (rows * cols - digits).max(1) as f64;
+    let number_chance = numbers as f64 / free_cells;
+    let style = Style {
+        symbols: &symbols,
+        number_chance,
+        edge_number_chance: number_chance,
+        symbol_chance: symbol_count as f64 / free_cells / (1.0 - number_chance).max(0.01),
+        max_digits,
+    };
+    draw(rng, rows, cols, &style)
+}
@@ -1,27 +1,13 @@
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 
+use common::{
+    bytes::Lines, hash::HashSet, rng::Rng, trace, Answer, Extras, ParseError, Part, Solution,
+};
+use grid::{Grid, Pos};
 use itertools::Itertools;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Pos {
-    pub row: usize,
-    pub col: usize,
-}
-
-impl Pos {
-    pub fn neighbors(&self) -> Vec<Pos> {
-        const OFFSETS: &[isize] = &[-1isize, 0, 1];
-        OFFSETS
-            .iter()
-            .cartesian_product(OFFSETS)
-            .map(|(dr, dc)| Pos {
-                row: (self.row as isize + dr) as usize,
-                col: (self.col as isize + dc) as usize,
-            })
-            .filter(|pos: &Pos| pos != self)
-            .collect()
-    }
-}
+mod generate;
+mod stream;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Number {
@@ -30,10 +16,22 @@ pub struct Number {
 }
 
 impl Number {
-    pub fn is_adjacent_to(&self, locations: &HashSet<&Pos>) -> bool {
-        self.positions
-            .iter()
-            .flat_map(|pos| pos.neighbors())
+    /// Where the number starts, as trace fields.
+    fn trace_fields(&self) -> Extras {
+        let Pos { row, col } = self.positions[0];
+        vec![
+            ("number", self.number.into()),
+            ("row", row.into()),
+            ("col", col.into()),
+        ]
+    }
+
+    pub fn neighbors<'a>(&'a self, grid: &'a Grid<char>) -> impl Iterator<Item = Pos> + 'a {
+        self.positions.iter().flat_map(|&pos| grid.neighbors8(pos))
+    }
+
+    pub fn is_adjacent_to(&self, grid: &Grid<char>, locations: &HashSet<&Pos>) -> bool {
+        self.neighbors(grid)
             .any(|neighbor| locations.contains(&neighbor))
     }
 }
@@ -50,9 +48,10 @@ pub enum Element {
     Symbol(Symbol),
 }
 
-fn parse_leading_number(line: &str, row: usize, col: usize) -> Number {
+fn parse_leading_number(line: &[char], row: usize, col: usize) -> Number {
     let digits: Vec<_> = line
-        .char_indices()
+        .iter()
+        .enumerate()
         .skip(col)
         .take_while(|(_, char)| char.is_ascii_digit())
         .collect();
@@ -62,16 +61,17 @@ fn parse_leading_number(line: &str, row: usize, col: usize) -> Number {
         .enumerate()
         .map(|(i, digit)| digit * (10u32).pow((digits.len() - i - 1) as u32))
         .sum();
-    return Number {
+    Number {
         number,
         positions: digits.iter().map(|(i, _)| Pos { row, col: *i }).collect(),
-    };
+    }
 }
 
-fn parse_line(line: &str, row: usize) -> Vec<Element> {
-    let mut visited = HashSet::new();
-    line.char_indices()
-        .flat_map(|(col, char)| {
+fn parse_line(line: &[char], row: usize) -> Vec<Element> {
+    let mut visited = HashSet::default();
+    line.iter()
+        .enumerate()
+        .flat_map(|(col, &char)| {
             if visited.contains(&col) {
                 return None;
             }
@@ -94,22 +94,138 @@ fn parse_line(line: &str, row: usize) -> Vec<Element> {
         .collect()
 }
 
-pub fn parse(input: &str) -> Vec<Element> {
-    input
-        .lines()
+pub fn parse(grid: &Grid<char>) -> Vec<Element> {
+    grid.iter_rows()
         .enumerate()
         .flat_map(|(row, line)| parse_line(line, row))
         .collect()
 }
 
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub elements: Vec<Element>,
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input<'a> = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let grid = Grid::parse(input, "any character", Some)?;
+        let elements = parse(&grid);
+        Ok(Schematic { grid, elements })
+    }
+
+    fn part1(Schematic { grid, elements }: &Self::Input<'_>) -> Answer {
+        let symbol_positions: HashSet<&Pos> = elements
+            .iter()
+            .filter_map(|e| match e {
+                Element::Symbol(Symbol { position, .. }) => Some(position),
+                _ => None,
+            })
+            .collect();
+
+        let numbers = elements.iter().filter_map(|e| match e {
+            Element::Number(n) => Some(n),
+            _ => None,
+        });
+        numbers
+            .filter(|number| {
+                let is_part_number = number.is_adjacent_to(grid, &symbol_positions);
+                match is_part_number {
+                    true => trace::info("part_number", || number.trace_fields()),
+                    false => trace::debug("not_part_number", || number.trace_fields()),
+                }
+                is_part_number
+            })
+            .map(|number| number.number)
+            .sum::<u32>()
+            .into()
+    }
+
+    fn part2(Schematic { grid, elements }: &Self::Input<'_>) -> Answer {
+        let possible_gear_positions: HashSet<&Pos> = elements
+            .iter()
+            .flat_map(|e| match e {
+                Element::Symbol(Symbol {
+                    char: '*',
+                    position,
+                }) => Some(position),
+                _ => None,
+            })
+            .collect();
+
+        // Ordered so that gears are traced top to bottom.
+        let mut gear_candidates: BTreeMap<Pos, Vec<Number>> = BTreeMap::new();
+
+        let numbers = elements.iter().filter_map(|e| match e {
+            Element::Number(n) => Some(n),
+            _ => None,
+        });
+        numbers.for_each(|number| {
+            let adjacent_gears = number
+                .neighbors(grid)
+                .filter(|neighbor| possible_gear_positions.contains(&neighbor))
+                .unique();
+            adjacent_gears.for_each(|gear_position| {
+                gear_candidates
+                    .entry(gear_position)
+                    .or_default()
+                    .push(number.clone());
+            })
+        });
+
+        gear_candidates
+            .iter()
+            .filter(|(&Pos { row, col }, numbers)| {
+                let is_gear = numbers.len() == 2;
+                let fields = || {
+                    vec![
+                        ("row", row.into()),
+                        ("col", col.into()),
+                        (
+                            "numbers",
+                            numbers.iter().map(|n| n.number).collect_vec().into(),
+                        ),
+                    ]
+                };
+                match is_gear {
+                    true => trace::info("gear", fields),
+                    false => trace::debug("not_gear", fields),
+                }
+                is_gear
+            })
+            .map(|(_, numbers)| numbers.iter().map(|number| number.number).product::<u32>())
+            .sum::<u32>()
+            .into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(schematic: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(schematic, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn chars(line: &str) -> Vec<char> {
+        line.chars().collect()
+    }
+
     #[test]
     fn test_parse_line() {
         assert_eq!(
-            parse_line("123", 3),
+            parse_line(&chars("123"), 3),
             vec![Element::Number(Number {
                 number: 123,
                 positions: vec![
@@ -120,7 +236,7 @@ mod tests {
             }),]
         );
         assert_eq!(
-            parse_line(".123...456", 3),
+            parse_line(&chars(".123...456"), 3),
             vec![
                 Element::Number(Number {
                     number: 123,
@@ -0,0 +1,108 @@
+//! Solving a row at a time over bytes, for inputs too big to read in whole. Numbers only touch
+//! symbols in the rows just above and below, so only three rows are kept at once.
+
+use common::{
+    bytes::{self, Lines},
+    Answer, ParseError, Part,
+};
+
+fn is_symbol(cell: u8) -> bool {
+    cell != b'.' && !cell.is_ascii_digit()
+}
+
+/// The value of the number that starts at `start` in `row`.
+fn number_at(row: &[u8], start: usize) -> u64 {
+    row[start..]
+        .iter()
+        .take_while(|cell| cell.is_ascii_digit())
+        .fold(0, |value, digit| value * 10 + u64::from(digit - b'0'))
+}
+
+/// Where each number in `row` starts and ends.
+fn numbers(row: &[u8]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut col = 0;
+    std::iter::from_fn(move || {
+        let start = col + row[col..].iter().position(u8::is_ascii_digit)?;
+        let end = row[start..]
+            .iter()
+            .position(|cell| !cell.is_ascii_digit())
+            .map_or(row.len(), |len| start + len);
+        col = end;
+        Some((start, end))
+    })
+}
+
+/// The numbers in `row` that touch column `col`, one column either side included.
+fn numbers_near(row: &[u8], col: usize) -> impl Iterator<Item = u64> + '_ {
+    let first = col.saturating_sub(1);
+    (first..row.len().min(col + 2))
+        .filter(move |&c| {
+            row[c].is_ascii_digit() && (c == first || c == 0 || !row[c - 1].is_ascii_digit())
+        })
+        .map(move |c| {
+            let start = row[..c]
+                .iter()
+                .rposition(|cell| !cell.is_ascii_digit())
+                .map_or(0, |i| i + 1);
+            number_at(row, start)
+        })
+}
+
+/// The answer's share from `row`, given the rows on either side of it.
+fn solve_row(part: Part, rows: [&[u8]; 3]) -> u64 {
+    let [_, row, _] = rows;
+    match part {
+        Part::One => numbers(row)
+            .filter(|&(start, end)| {
+                let cols = start.saturating_sub(1)..row.len().min(end + 1);
+                rows.iter().any(|row| {
+                    row.get(cols.clone())
+                        .is_some_and(|cells| cells.iter().any(|&c| is_symbol(c)))
+                })
+            })
+            .map(|(start, _)| number_at(row, start))
+            .sum(),
+        Part::Two => (0..row.len())
+            .filter(|&col| row[col] == b'*')
+            .filter_map(|col| {
+                let mut near = rows.iter().flat_map(|row| numbers_near(row, col));
+                match (near.next(), near.next(), near.next()) {
+                    (Some(a), Some(b), None) => Some(a * b),
+                    _ => None,
+                }
+            })
+            .sum(),
+    }
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    // The rows above, at and below the one being solved, reused as they scroll by.
+    let mut rows = [Vec::new(), Vec::new(), Vec::new()];
+    let mut width = None;
+    let mut seen = 0;
+    let mut sum = 0;
+    while let Some((number, line)) = lines.next_line() {
+        bytes::grid_row(number, line, &mut width)?;
+        if let Some(offset) = line.iter().position(|cell| !cell.is_ascii()) {
+            return Err(ParseError::in_line(
+                number,
+                line,
+                offset,
+                "an ASCII character",
+            ));
+        }
+        rows.rotate_left(1);
+        rows[2].clear();
+        rows[2].extend_from_slice(line);
+        seen += 1;
+        if seen > 1 {
+            sum += solve_row(part, [&rows[0], &rows[1], &rows[2]]);
+        }
+    }
+    if seen > 0 {
+        rows.rotate_left(1);
+        rows[2].clear();
+        sum += solve_row(part, [&rows[0], &rows[1], &rows[2]]);
+    }
+    Ok(sum.into())
+}
@@ -1,19 +1,8 @@
-use std::{env::args, fs};
+use std::process::ExitCode;
 
-use day04::Card;
+use common::Part;
+use day04::Day04;
 
-fn main() {
-    let input = fs::read_to_string(args().nth(1).unwrap()).unwrap();
-    let cards: Vec<Card> = input.lines().map(Card::parse).collect();
-
-    let mut counts = vec![1; cards.len()];
-
-    for (i, card) in cards.iter().enumerate() {
-        for j in 0..card.match_count() {
-            counts[i + j + 1] += counts[i];
-        }
-    }
-    let result: usize = counts.iter().sum();
-
-    dbg!(result);
+fn main() -> ExitCode {
+    common::main::<Day04>(Part::Two)
 }
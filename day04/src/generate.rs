@@ -0,0 +1,96 @@
+use common::rng::Rng;
+
+use crate::Card;
+
+/// How the cards are laid out.
+struct Layout {
+    winning_count: usize,
+    number_count: usize,
+    max_number: i32,
+    /// Width of the card numbers.
+    width: usize,
+}
+
+/// Card `id`, with `matches` of its numbers among its winning numbers.
+fn card(rng: &mut Rng, id: usize, matches: usize, layout: &Layout) -> String {
+    let Layout {
+        winning_count,
+        number_count,
+        width,
+        ..
+    } = *layout;
+    let mut pool = (1..=layout.max_number).collect::<Vec<i32>>();
+    rng.shuffle(&mut pool);
+    let winning = &pool[..winning_count];
+    let mut numbers = winning[..matches].to_vec();
+    numbers.extend(&pool[winning_count..winning_count + number_count - matches]);
+    rng.shuffle(&mut numbers);
+
+    let format = |numbers: &[i32]| {
+        numbers
+            .iter()
+            .map(|n| format!("{n:2}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    format!(
+        "Card {id:>width$}: {} | {}\n",
+        format(winning),
+        format(&numbers)
+    )
+}
+
+/// Scratchcards laid out like the real input, with ten winning numbers and twenty-five numbers
+/// on each card, or five and eight for small sizes. No card wins more copies than there are
+/// cards after it.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let (winning_count, number_count) = if size < 10 { (5, 8) } else { (10, 25) };
+    let layout = Layout {
+        winning_count,
+        number_count,
+        max_number: 99,
+        width: size.to_string().len(),
+    };
+
+    (1..=size)
+        .map(|id| {
+            let matches = rng.range(0..=winning_count.min(size - id) as i64) as usize;
+            card(rng, id, matches, &layout)
+        })
+        .collect()
+}
+
+/// As many cards as `cards`, with as many numbers of each kind and numbers no larger. Each card
+/// matches as many numbers as the card in the same place in `cards`, or one fewer, so copies pile
+/// up about as quickly. More matches could make part 2's counts grow without bound, but fewer
+/// never make them larger than they were.
+pub fn lookalike(cards: &[Card], rng: &mut Rng) -> String {
+    let (winning_count, number_count) = cards
+        .first()
+        .map_or((1, 1), |card| (card.winning.len(), card.numbers.len()));
+    let largest = cards
+        .iter()
+        .flat_map(|card| card.winning.iter().chain(&card.numbers))
+        .max()
+        .copied()
+        .unwrap_or(1);
+    let layout = Layout {
+        winning_count,
+        number_count,
+        // The winning numbers and the others are drawn from a pool with room for all of them.
+        max_number: largest.max((winning_count + number_count) as i32),
+        width: cards.len().to_string().len(),
+    };
+
+    cards
+        .iter()
+        .enumerate()
+        .map(|(i, original)| {
+            let matches = original.match_count().saturating_sub(rng.index(2));
+            let matches = matches
+                .min(winning_count.min(number_count))
+                .min(cards.len() - i - 1);
+            card(rng, i + 1, matches, &layout)
+        })
+        .collect()
+}
@@ -1,14 +1,22 @@
-use std::collections::HashSet;
-
+use common::{
+    bytes::Lines,
+    hash::HashSet,
+    parse::{self, finish, IResult},
+    rng::Rng,
+    trace, Answer, ParseError, Part, Solution,
+};
 use nom::{
     bytes::complete::tag,
-    character::complete,
-    multi::{many1, separated_list1},
+    character::complete::{self, space1},
+    error::context,
     sequence::preceded,
-    IResult,
 };
 
+mod generate;
+mod stream;
+
 pub struct Card {
+    pub id: u32,
     pub winning: Vec<i32>,
     pub numbers: Vec<i32>,
 }
@@ -20,22 +28,148 @@ impl Card {
         winning.intersection(&numbers).count()
     }
 
-    fn card_parser(input: &str) -> IResult<&str, Card> {
-        let (input, _) = tag("Card")(input)?;
-        let (input, _) = preceded(many1(tag(" ")), complete::i32)(input)?;
-        let (input, _) = tag(":")(input)?;
-        let (input, _) = many1(tag(" "))(input)?;
-        let (input, winning) = separated_list1(many1(tag(" ")), complete::i32)(input)?;
-        let (input, _) = tag(" |")(input)?;
-        let (input, _) = many1(tag(" "))(input)?;
-        let (input, numbers) = separated_list1(many1(tag(" ")), complete::i32)(input)?;
+    fn card_parser(input: &str) -> IResult<'_, Card> {
+        let (input, _) = context("`Card`", tag("Card"))(input)?;
+        let (input, id) = preceded(space1, complete::u32)(input)?;
+        let (input, _) = context("`:`", tag(":"))(input)?;
+        let (input, winning) = preceded(space1, parse::numbers(complete::i32))(input)?;
+        let (input, _) = context("` |`", tag(" |"))(input)?;
+        let (input, numbers) = preceded(space1, parse::numbers(complete::i32))(input)?;
+
+        Ok((
+            input,
+            Card {
+                id,
+                winning,
+                numbers,
+            },
+        ))
+    }
+
+    pub fn parse(input: &str) -> Result<Card, ParseError> {
+        finish(input, Card::card_parser(input))
+    }
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input<'a> = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse::lines(input, Card::card_parser)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let cards = match parse::check_lines(input, input, Card::card_parser) {
+            Ok(cards) => cards,
+            Err(errors) => return errors,
+        };
+
+        // Part two goes by position, so the numbers have to count up from 1, and a card can't win
+        // copies of cards past the end of the table.
+        let mut problems = Vec::new();
+        for ((i, card), line) in cards.iter().enumerate().zip(input.lines()) {
+            if card.id as usize != i + 1 {
+                let number = line.trim_start_matches("Card").trim_start();
+                let expected = format!("card number {}", i + 1);
+                problems.push(ParseError::new(input, number, expected));
+            }
+            let left = cards.len() - i - 1;
+            if card.match_count() > left {
+                let expected =
+                    format!("at most {left} matching number(s), as only {left} card(s) follow");
+                problems.push(ParseError::new(input, line, expected));
+            }
+        }
+        problems
+    }
+
+    fn part1(cards: &Self::Input<'_>) -> Answer {
+        cards
+            .iter()
+            .enumerate()
+            .map(|(i, card)| {
+                let count = card.match_count();
+                let points = match count {
+                    0 => 0,
+                    _ => 2_i32.pow(count as u32 - 1),
+                };
+                trace::info("card", || {
+                    vec![
+                        ("card", (i + 1).into()),
+                        ("matches", count.into()),
+                        ("points", points.into()),
+                    ]
+                });
+                points
+            })
+            .sum::<i32>()
+            .into()
+    }
+
+    fn part2(cards: &Self::Input<'_>) -> Answer {
+        let mut counts = vec![1; cards.len()];
+
+        for (i, card) in cards.iter().enumerate() {
+            let matches = card.match_count();
+            // Cards are numbered from 1, so the ones won are numbered from `i + 2`.
+            trace::info("card", || {
+                vec![
+                    ("card", (i + 1).into()),
+                    ("instances", counts[i].into()),
+                    ("matches", matches.into()),
+                    (
+                        "copies_of",
+                        ((i + 2)..(i + 2 + matches)).collect::<Vec<_>>().into(),
+                    ),
+                ]
+            });
+            for j in 0..matches {
+                counts[i + j + 1] += counts[i];
+            }
+        }
+        counts.iter().sum::<usize>().into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(cards: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(cards, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
 
-        assert!(input.is_empty());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok((input, Card { winning, numbers }))
+    #[test]
+    fn test_parse_error() {
+        let input = "Card 1: 41 48 | 83 86\nCard 2: 13 32 / 61 30";
+        let error = Day04::parse(input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 14));
+        assert_eq!(error.expected, "` |`");
     }
 
-    pub fn parse(input: &str) -> Card {
-        Card::card_parser(input).unwrap().1
+    #[test]
+    fn test_trace_copies() {
+        let input = "Card 1: 41 48 | 48 41\nCard 2: 13 32 | 32 1\nCard 3: 5 | 6";
+        let cards = Day04::parse(input).unwrap();
+        let (answer, events) = trace::capture(trace::Level::Info, || Day04::part2(&cards));
+        assert_eq!(answer, Answer::from(7));
+        assert_eq!(
+            events.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "card card=1 instances=1 matches=2 copies_of=[2,3]",
+                "card card=2 instances=2 matches=1 copies_of=[3]",
+                "card card=3 instances=4 matches=0 copies_of=[]",
+            ]
+        );
     }
 }
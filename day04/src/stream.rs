@@ -0,0 +1,73 @@
+//! Solving a card at a time over bytes, for inputs too big to read in whole.
+
+use std::collections::VecDeque;
+
+use common::{
+    bytes::{Cursor, Lines},
+    Answer, ParseError, Part,
+};
+
+/// Reads a card's winning numbers and numbers into `winning` and `numbers`, which are reused from
+/// card to card, and returns how many of its numbers win.
+fn match_count(
+    cursor: &mut Cursor,
+    winning: &mut Vec<i64>,
+    numbers: &mut Vec<i64>,
+) -> Result<usize, ParseError> {
+    cursor.expect("Card")?;
+    if !cursor.spaces() {
+        return Err(cursor.error("a space"));
+    }
+    cursor.unsigned()?;
+    cursor.expect(":")?;
+
+    winning.clear();
+    loop {
+        cursor.spaces();
+        if cursor.eat("|") {
+            break;
+        }
+        winning.push(cursor.signed()?);
+    }
+    numbers.clear();
+    while let Some(number) = cursor.next_number() {
+        numbers.push(number?);
+    }
+
+    winning.sort_unstable();
+    winning.dedup();
+    numbers.sort_unstable();
+    numbers.dedup();
+    Ok(numbers
+        .iter()
+        .filter(|number| winning.binary_search(number).is_ok())
+        .count())
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let (mut winning, mut numbers) = (Vec::new(), Vec::new());
+    // Copies won of the cards after this one, which are all that part two needs to remember.
+    let mut copies = VecDeque::<u64>::new();
+    let mut total = 0u64;
+    while let Some((number, line)) = lines.next_line() {
+        let matches = match_count(&mut Cursor::new(number, line), &mut winning, &mut numbers)?;
+        match part {
+            Part::One => {
+                if matches > 0 {
+                    total += 1 << (matches - 1);
+                }
+            }
+            Part::Two => {
+                let instances = 1 + copies.pop_front().unwrap_or(0);
+                if copies.len() < matches {
+                    copies.resize(matches, 0);
+                }
+                for won in copies.iter_mut().take(matches) {
+                    *won += instances;
+                }
+                total += instances;
+            }
+        }
+    }
+    Ok(total.into())
+}
@@ -0,0 +1,176 @@
+use std::{collections::BTreeSet, fmt};
+
+use common::{hash::HashMap, rng::Rng};
+use itertools::Itertools;
+
+use crate::{Almanac, Map, MapEntry};
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// An almanac with the usual chain of maps from seed to location, shown as puzzle input when
+/// debug-printed.
+#[derive(Clone)]
+pub struct Case(pub Almanac<'static>);
+
+impl Case {
+    /// The maps in the order they're followed.
+    pub(crate) fn maps(&self) -> impl Iterator<Item = &Map<'static>> {
+        CATEGORIES[..CATEGORIES.len() - 1]
+            .iter()
+            .map(|category| &self.0.map_by_type[category])
+    }
+
+    /// The almanac in the puzzle's input format.
+    pub fn to_input(&self) -> String {
+        format_almanac(&self.0.seeds, self.maps())
+    }
+}
+
+/// `seeds` and `maps` in the puzzle's input format, with the maps in the order given.
+fn format_almanac<'a, 'b: 'a>(seeds: &[i64], maps: impl Iterator<Item = &'a Map<'b>>) -> String {
+    let seeds = seeds.iter().join(" ");
+    let mut maps = maps.map(|map| {
+        let mut entries = map
+            .entries
+            .iter()
+            .map(|entry| format!("{} {} {}", entry.destination, entry.source, entry.length));
+        format!("{}-to-{} map:\n{}", map.from, map.to, entries.join("\n"))
+    });
+    format!("seeds: {seeds}\n\n{}\n", maps.join("\n\n"))
+}
+
+impl fmt::Debug for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\n{}", self.to_input())
+    }
+}
+
+/// Up to `size / 2 + 1` seed ranges and `size` entries per map, with numbers up to `30 * size`.
+/// Each map's entries cover separate source ranges, listed in no particular order.
+pub fn generate_case(rng: &mut Rng, size: usize) -> Case {
+    let size = size.max(1);
+    let scale = 30 * size as i64;
+    let seeds = (0..rng.range(1..=(size as i64 / 2 + 1).min(10)))
+        .flat_map(|_| [rng.range(0..=scale), rng.range(1..=scale / 6)])
+        .collect();
+
+    let map_by_type = CATEGORIES
+        .iter()
+        .tuple_windows()
+        .map(|(&from, &to)| {
+            let mut source = 0;
+            let mut entries = (0..rng.range(1..=size as i64))
+                .map(|_| {
+                    source += rng.range(0..=scale / 12);
+                    let entry = MapEntry {
+                        source,
+                        destination: rng.range(0..=scale),
+                        length: rng.range(1..=scale / 8),
+                    };
+                    source += entry.length;
+                    entry
+                })
+                .collect_vec();
+            rng.shuffle(&mut entries);
+            (from, Map { from, to, entries })
+        })
+        .collect::<HashMap<_, _>>();
+
+    Case(Almanac { seeds, map_by_type })
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    generate_case(rng, size).to_input()
+}
+
+/// An almanac with the same categories as `almanac`, as many seed ranges and as many entries in
+/// each map, with every number up to the largest in `almanac` and seed ranges no longer. Like in
+/// the real inputs, each map's entries cover a run of sources without gaps and send it to a run
+/// of destinations of the same length, in a shuffled order.
+pub fn lookalike(almanac: &Almanac, rng: &mut Rng) -> String {
+    let mut maps = Vec::new();
+    let mut current = "seed";
+    while let Some(map) = almanac.map_by_type.get(current) {
+        if maps.len() == almanac.map_by_type.len() {
+            break;
+        }
+        maps.push(map);
+        current = map.to;
+    }
+
+    let ends = maps.iter().flat_map(|map| &map.entries).flat_map(|entry| {
+        [
+            entry.source + entry.length,
+            entry.destination + entry.length,
+        ]
+    });
+    let seed_ends = almanac.seeds.chunks(2).map(|pair| pair.iter().sum());
+    let entry_count = maps.iter().map(|map| map.entries.len()).max().unwrap_or(0);
+    let scale = ends
+        .chain(seed_ends)
+        .max()
+        .unwrap_or(0)
+        .max(entry_count as i64 + 1);
+    let longest_seed_range = almanac.seeds.chunks(2).filter_map(|pair| pair.get(1)).max();
+    let longest_seed_range = longest_seed_range.copied().unwrap_or(1).clamp(1, scale);
+
+    let seeds = almanac
+        .seeds
+        .chunks(2)
+        .flat_map(|pair| match pair.len() {
+            2 => {
+                let length = rng.range(1..=longest_seed_range);
+                vec![rng.range(0..=scale - length), length]
+            }
+            _ => vec![rng.range(0..=scale)],
+        })
+        .collect_vec();
+
+    let maps = maps
+        .iter()
+        .map(|map| {
+            let mut bounds = BTreeSet::new();
+            while bounds.len() <= map.entries.len() {
+                bounds.insert(rng.range(0..=scale));
+            }
+            let sources = bounds
+                .into_iter()
+                .tuple_windows()
+                .collect::<Vec<(i64, i64)>>();
+            let total = sources.last().unwrap().1 - sources[0].0;
+
+            let mut order = (0..sources.len()).collect_vec();
+            rng.shuffle(&mut order);
+            let mut destination = rng.range(0..=scale - total);
+            let mut entries = order
+                .into_iter()
+                .map(|i| {
+                    let (start, end) = sources[i];
+                    let entry = MapEntry {
+                        source: start,
+                        destination,
+                        length: end - start,
+                    };
+                    destination += entry.length;
+                    entry
+                })
+                .collect_vec();
+            rng.shuffle(&mut entries);
+            Map {
+                entries,
+                ..(*map).clone()
+            }
+        })
+        .collect_vec();
+
+    format_almanac(&seeds, maps.iter())
+}
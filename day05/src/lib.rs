@@ -1 +1,459 @@
+use common::{
+    budget,
+    bytes::Lines,
+    differential::Check,
+    hash::{HashMap, HashSet},
+    parse::{self, finish, IResult},
+    rng::Rng,
+    trace, Answer, ParseError, Part, Solution, Value,
+};
+use itertools::Itertools;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alpha1},
+    combinator::{cut, map_opt},
+    error::context,
+    multi::separated_list1,
+};
 
+mod generate;
+pub mod reference;
+mod stream;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Span {
+    start: i64,
+    length: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct MapEntry {
+    source: i64,
+    destination: i64,
+    length: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Map<'a> {
+    from: &'a str,
+    to: &'a str,
+    entries: Vec<MapEntry>,
+}
+
+impl MapEntry {
+    fn to_value(self) -> Value {
+        Value::Object(vec![
+            ("destination", self.destination.into()),
+            ("source", self.source.into()),
+            ("length", self.length.into()),
+        ])
+    }
+
+    fn parse(input: &str) -> IResult<'_, MapEntry> {
+        let entry = map_opt(parse::numbers(complete::i64), |numbers| match numbers[..] {
+            [destination, source, length] => Some(MapEntry {
+                source,
+                destination,
+                length,
+            }),
+            _ => None,
+        });
+        context("three numbers", entry)(input)
+    }
+
+    fn apply(&self, span: &Span) -> (Vec<Span>, Vec<Span>) {
+        let span_range = span.start..(span.start + span.length);
+        let entry_range = self.source..(self.source + self.length);
+        let entry_offset = self.destination - self.source;
+
+        // [ ] < >
+        // < > [ ]
+        let is_entirely_before = span_range.end <= entry_range.start;
+        let is_entirely_after = span_range.start >= entry_range.end;
+
+        if is_entirely_before || is_entirely_after {
+            return (vec![span.clone()], vec![]);
+        }
+
+        // < [ ] >
+        let is_entirely_contained =
+            span_range.start >= entry_range.start && span_range.end <= entry_range.end;
+        if is_entirely_contained {
+            return (
+                vec![],
+                vec![Span {
+                    start: span_range.start + entry_offset,
+                    length: span.length,
+                }],
+            );
+        }
+
+        // [ < > ]
+        let is_super_span_of_entry =
+            span_range.start < entry_range.start && span_range.end > entry_range.end;
+        if is_super_span_of_entry {
+            return (
+                vec![
+                    Span {
+                        start: span_range.start,
+                        length: entry_range.start - span_range.start,
+                    },
+                    Span {
+                        start: entry_range.end,
+                        length: span_range.end - entry_range.end,
+                    },
+                ],
+                vec![Span {
+                    start: entry_range.start + entry_offset,
+                    length: entry_range.end - entry_range.start,
+                }],
+            );
+        }
+        // [ < ] >
+        let is_left_overlap = span_range.start < entry_range.start;
+        if is_left_overlap {
+            return (
+                vec![Span {
+                    start: span_range.start,
+                    length: entry_range.start - span_range.start,
+                }],
+                vec![Span {
+                    start: entry_range.start + entry_offset,
+                    length: span_range.end - entry_range.start,
+                }],
+            );
+        }
+        // < [ > ]
+        let is_right_overlap = span_range.start >= entry_range.start;
+        if is_right_overlap {
+            return (
+                vec![Span {
+                    start: entry_range.end,
+                    length: span_range.end - entry_range.end,
+                }],
+                vec![Span {
+                    start: span_range.start + entry_offset,
+                    length: entry_range.end - span_range.start,
+                }],
+            );
+        }
+
+        unreachable!();
+    }
+}
+
+impl<'a> Map<'a> {
+    fn parse(input: &'a str) -> IResult<'a, Map<'a>> {
+        let (input, from) = alpha1(input)?;
+        let (input, _) = context("`-to-`", tag("-to-"))(input)?;
+        let (input, to) = alpha1(input)?;
+        let (input, _) = context("` map:`", tag(" map:\n"))(input)?;
+        let (input, entries) = separated_list1(tag("\n"), MapEntry::parse)(input)?;
+
+        Ok((input, Map { from, to, entries }))
+    }
+}
+
+fn parse_seeds(input: &str) -> IResult<'_, Vec<i64>> {
+    let (input, seeds) = parse::labelled("seeds", parse::numbers(complete::i64))(input)?;
+    let (input, _) = parse::blank_line(input)?;
+
+    Ok((input, seeds))
+}
+
+fn parse_maps(input: &str) -> IResult<'_, Vec<Map<'_>>> {
+    parse::sections(cut(Map::parse))(input)
+}
+
+/// The seeds and maps, with the maps in the order they're listed.
+fn parse_almanac(input: &str) -> IResult<'_, (Vec<i64>, Vec<Map<'_>>)> {
+    let (input, seeds) = parse_seeds(input)?;
+    let (input, maps) = parse_maps(input)?;
+    Ok((input, (seeds, maps)))
+}
+
+fn follow_maps(map_by_type: &HashMap<&str, Map>, seed: i64, destination_type: &str) -> i64 {
+    let mut value = seed;
+    let mut current_type = "seed";
+
+    while current_type != destination_type {
+        budget::step();
+        let map = &map_by_type[current_type];
+        let found_value = map
+            .entries
+            .iter()
+            .fold(None, |found_value, entry| match found_value {
+                None => {
+                    if (entry.source..(entry.source + entry.length)).contains(&value) {
+                        Some(entry.destination + (value - entry.source))
+                    } else {
+                        None
+                    }
+                }
+                Some(_) => found_value,
+            });
+        let next_type = map.to;
+        trace::debug("mapped", || {
+            vec![
+                ("from", current_type.into()),
+                ("to", next_type.into()),
+                ("value", value.into()),
+                ("result", found_value.unwrap_or(value).into()),
+            ]
+        });
+        if let Some(new_value) = found_value {
+            value = new_value;
+        }
+        current_type = next_type;
+    }
+
+    trace::info("seed", || {
+        vec![("seed", seed.into()), ("location", value.into())]
+    });
+    value
+}
+
+fn follow_maps_for_span(
+    map_by_type: &HashMap<&str, Map>,
+    seed: &Span,
+    destination_type: &str,
+) -> i64 {
+    let mut spans = vec![seed.clone()];
+    let mut current_type = "seed";
+
+    while current_type != destination_type {
+        budget::step();
+        let map = &map_by_type[current_type];
+        let mut old_spans = spans.clone();
+        let mut new_spans = Vec::new();
+
+        for entry in map.entries.iter() {
+            let mut spans_to_retry = Vec::new();
+
+            while let Some(span) = old_spans.pop() {
+                let (unaffected, affected) = entry.apply(&span);
+                for moved in &affected {
+                    trace::info("span", || {
+                        vec![
+                            ("map", format!("{}-to-{}", map.from, map.to).into()),
+                            (
+                                "start",
+                                (moved.start - entry.destination + entry.source).into(),
+                            ),
+                            ("length", moved.length.into()),
+                            ("entry", entry.to_value()),
+                            ("to_start", moved.start.into()),
+                        ]
+                    });
+                }
+                new_spans.extend(affected);
+                spans_to_retry.extend(unaffected);
+            }
+
+            old_spans = spans_to_retry;
+        }
+        for span in &old_spans {
+            trace::info("span_unmapped", || {
+                vec![
+                    ("map", format!("{}-to-{}", map.from, map.to).into()),
+                    ("start", span.start.into()),
+                    ("length", span.length.into()),
+                ]
+            });
+        }
+        spans = old_spans.clone();
+        spans.extend(new_spans);
+        current_type = map.to;
+    }
+
+    let location = spans.iter().map(|span| span.start).min().unwrap();
+    trace::info("seed_range", || {
+        vec![
+            ("start", seed.start.into()),
+            ("length", seed.length.into()),
+            ("location", location.into()),
+        ]
+    });
+    location
+}
+
+const DEST: &str = "location";
+
+/// An error unless the seeds pair up, as part two reads them as start and length pairs.
+fn odd_seeds(input: &str, seeds: &[i64]) -> Option<ParseError> {
+    let seeds_line = input.lines().next()?;
+    (!seeds.len().is_multiple_of(2)).then(|| {
+        ParseError::at_offset(
+            input,
+            seeds_line.len(),
+            "an even number of seeds, as part two reads them as start and length pairs",
+        )
+    })
+}
+
+/// An error unless following the maps from seeds leads to locations without going round in
+/// circles. Where there's more than one map from a type, the last one is followed.
+fn broken_route(input: &str, maps: &[Map]) -> Option<ParseError> {
+    let map_by_type: HashMap<_, _> = maps.iter().map(|map| (map.from, map)).collect();
+    let mut current = "seed";
+    let mut visited = HashSet::default();
+    while current != DEST {
+        let Some(map) = map_by_type.get(current) else {
+            let expected = format!("a map from `{current}`");
+            return Some(ParseError::at_offset(input, input.len(), expected));
+        };
+        if !visited.insert(current) {
+            let expected = format!("maps that lead from `seed` to `{DEST}` without a loop");
+            return Some(ParseError::new(input, map.from, expected));
+        }
+        current = map.to;
+    }
+    None
+}
+
+/// Parsing makes sure the seeds pair up and that there's a map from every type on the way from
+/// `seed` to `location`, so the solvers never come up short.
+#[derive(Debug, Clone)]
+pub struct Almanac<'a> {
+    seeds: Vec<i64>,
+    map_by_type: HashMap<&'a str, Map<'a>>,
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input<'a> = Almanac<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (seeds, maps) = finish(input, parse_almanac(input))?;
+        if let Some(error) = odd_seeds(input, &seeds).or_else(|| broken_route(input, &maps)) {
+            return Err(error);
+        }
+        let map_by_type = maps.into_iter().map(|map| (map.from, map)).collect();
+        Ok(Almanac { seeds, map_by_type })
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let (seeds, maps) = match finish(input, parse_almanac(input)) {
+            Ok(almanac) => almanac,
+            Err(error) => return vec![error],
+        };
+
+        let mut problems = Vec::new();
+        problems.extend(odd_seeds(input, &seeds));
+        let mut from = HashSet::default();
+        for map in &maps {
+            if !from.insert(map.from) {
+                let expected = format!("only one map from `{}`", map.from);
+                problems.push(ParseError::new(input, map.from, expected));
+            }
+        }
+        problems.extend(broken_route(input, &maps));
+        problems
+    }
+
+    fn part1(almanac: &Self::Input<'_>) -> Answer {
+        almanac
+            .seeds
+            .iter()
+            .map(|&seed| follow_maps(&almanac.map_by_type, seed, DEST))
+            .min()
+            .expect("parsing makes sure there are seeds")
+            .into()
+    }
+
+    fn part2(almanac: &Self::Input<'_>) -> Answer {
+        almanac
+            .seeds
+            .iter()
+            .tuples()
+            .map(|(&start, &length)| Span { start, length })
+            .map(|seed| follow_maps_for_span(&almanac.map_by_type, &seed, DEST))
+            .min()
+            .expect("parsing makes sure there are seeds")
+            .into()
+    }
+
+    fn differentials() -> Vec<Box<dyn Check>> {
+        reference::differentials()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(almanac: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(almanac, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::differential;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = "seed-to-soil map:\n\
+            50 98 2\n\
+            52 50 48";
+        assert_eq!(
+            Map::parse(input),
+            Ok((
+                "",
+                Map {
+                    from: "seed",
+                    to: "soil",
+                    entries: vec![
+                        MapEntry {
+                            destination: 50,
+                            source: 98,
+                            length: 2,
+                        },
+                        MapEntry {
+                            destination: 52,
+                            source: 50,
+                            length: 48,
+                        },
+                    ],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        differential::assert_all(Day05::differentials());
+    }
+
+    #[test]
+    fn test_parse_rejects_what_the_solvers_cannot_follow() {
+        for (input, expected) in [
+            (
+                "seeds: 79 14 55\n\nseed-to-location map:\n50 98 2",
+                "an even number of seeds, as part two reads them as start and length pairs",
+            ),
+            (
+                "seeds: 79 14\n\nseed-to-soil map:\n50 98 2",
+                "a map from `soil`",
+            ),
+            (
+                "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n\nsoil-to-seed map:\n1 2 3",
+                "maps that lead from `seed` to `location` without a loop",
+            ),
+        ] {
+            assert_eq!(Day05::parse(input).unwrap_err().expected, expected);
+            assert_eq!(Day05::check(input)[0].expected, expected);
+            for part in [Part::One, Part::Two] {
+                assert!(Day05::stream(part, &mut Lines::from_bytes(input))
+                    .unwrap()
+                    .is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Slow but obviously correct versions of this day's solvers, for differential testing.
+
+use common::{
+    differential::{shrink_int, shrink_vec, Check, Differential},
+    Solution,
+};
+use itertools::Itertools;
+
+use crate::{
+    generate::{generate_case, Case},
+    Almanac, Day05, Map, MapEntry, DEST,
+};
+
+/// Looks up a single value in a map; values no entry covers map to themselves.
+fn convert(map: &Map, value: i64) -> i64 {
+    map.entries
+        .iter()
+        .find(|entry| (entry.source..entry.source + entry.length).contains(&value))
+        .map_or(value, |entry| value - entry.source + entry.destination)
+}
+
+/// Expands every seed range and follows each seed through the maps on its own.
+pub fn lowest_location_of_ranges(almanac: &Almanac) -> i64 {
+    almanac
+        .seeds
+        .iter()
+        .tuples()
+        .flat_map(|(&start, &length)| start..start + length)
+        .map(|seed| {
+            let mut category = "seed";
+            let mut value = seed;
+            while category != DEST {
+                let map = &almanac.map_by_type[category];
+                value = convert(map, value);
+                category = map.to;
+            }
+            value
+        })
+        .min()
+        .unwrap()
+}
+
+impl Case {
+    /// Whether the case is one the puzzle could give: at least one seed range, and each map
+    /// having entries that cover separate source ranges.
+    fn is_valid(&self) -> bool {
+        !self.0.seeds.is_empty()
+            && self
+                .0
+                .seeds
+                .iter()
+                .skip(1)
+                .step_by(2)
+                .all(|&length| length > 0)
+            && self.maps().all(|map| {
+                !map.entries.is_empty()
+                    && map
+                        .entries
+                        .iter()
+                        .map(|entry| (entry.source, entry.source + entry.length))
+                        .sorted()
+                        .tuple_windows()
+                        .all(|((_, end), (start, _))| end <= start)
+            })
+    }
+
+    fn with_seeds(&self, seeds: Vec<i64>) -> Case {
+        Case(Almanac {
+            seeds,
+            map_by_type: self.0.map_by_type.clone(),
+        })
+    }
+
+    fn with_entries(&self, category: &'static str, entries: Vec<MapEntry>) -> Case {
+        let mut case = self.clone();
+        case.0.map_by_type.get_mut(category).unwrap().entries = entries;
+        case
+    }
+}
+
+fn shrink(case: &Case) -> Vec<Case> {
+    let seeds = &case.0.seeds;
+    let mut variants = shrink_vec(&seeds.chunks(2).collect_vec())
+        .into_iter()
+        .map(|ranges| case.with_seeds(ranges.concat()))
+        .collect_vec();
+    for (i, &seed) in seeds.iter().enumerate() {
+        let target = if i % 2 == 0 { 0 } else { 1 };
+        variants.extend(shrink_int(seed, target).into_iter().map(|smaller| {
+            let mut seeds = seeds.clone();
+            seeds[i] = smaller;
+            case.with_seeds(seeds)
+        }));
+    }
+
+    for map in case.maps() {
+        let entries = &map.entries;
+        variants.extend(
+            shrink_vec(entries)
+                .into_iter()
+                .map(|entries| case.with_entries(map.from, entries)),
+        );
+        for (i, entry) in entries.iter().enumerate() {
+            let smaller = shrink_int(entry.source, 0)
+                .into_iter()
+                .map(|source| MapEntry { source, ..*entry })
+                .chain(
+                    shrink_int(entry.destination, 0)
+                        .into_iter()
+                        .map(|destination| MapEntry {
+                            destination,
+                            ..*entry
+                        }),
+                )
+                .chain(
+                    shrink_int(entry.length, 1)
+                        .into_iter()
+                        .map(|length| MapEntry { length, ..*entry }),
+                );
+            variants.extend(smaller.map(|smaller| {
+                let mut entries = entries.clone();
+                entries[i] = smaller;
+                case.with_entries(map.from, entries)
+            }));
+        }
+    }
+
+    variants.retain(Case::is_valid);
+    variants
+}
+
+pub fn differentials() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Differential {
+        name: "day05 part 2 (MapEntry::apply)",
+        generate: |rng| generate_case(rng, 4),
+        shrink,
+        fast: |case| Day05::part2(&case.0),
+        reference: |case| lowest_location_of_ranges(&case.0).into(),
+    })]
+}
@@ -0,0 +1,140 @@
+//! Solving a map entry at a time over bytes, for inputs too big to read in whole. The seeds are
+//! moved through each map as its entries are read, so the maps have to be listed in the order
+//! they're followed, from `seed` to `location`, as they are in puzzle inputs.
+
+use common::{
+    bytes::{Cursor, Lines},
+    Answer, ParseError, Part,
+};
+
+use crate::{MapEntry, Span, DEST};
+
+/// What the next line should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Blank,
+    Header,
+    FirstEntry,
+    Entry,
+}
+
+/// A map's header, returning what it maps from and to.
+fn parse_header<'a>(cursor: &mut Cursor<'a>) -> Result<(&'a [u8], &'a [u8]), ParseError> {
+    let from = cursor.word();
+    if from.is_empty() {
+        return Err(cursor.error("a map name"));
+    }
+    cursor.expect("-to-")?;
+    let to = cursor.word();
+    if to.is_empty() {
+        return Err(cursor.error("a map name"));
+    }
+    cursor.expect(" map:")?;
+    cursor.end()?;
+    Ok((from, to))
+}
+
+fn parse_entry(cursor: &mut Cursor) -> Result<MapEntry, ParseError> {
+    let start = cursor.clone();
+    let mut numbers = [0; 3];
+    for number in &mut numbers {
+        *number = cursor
+            .next_number()
+            .ok_or_else(|| start.error("three numbers"))??;
+    }
+    cursor.end()?;
+    let [destination, source, length] = numbers;
+    Ok(MapEntry {
+        source,
+        destination,
+        length,
+    })
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let (number, line) = lines.next_line().unwrap_or((1, b""));
+    let mut cursor = Cursor::new(number, line);
+    cursor.expect("seeds:")?;
+    let mut seeds = Vec::new();
+    while let Some(seed) = cursor.next_number() {
+        seeds.push(seed?);
+    }
+    if seeds.is_empty() {
+        return Err(cursor.error("a number"));
+    }
+    if seeds.len() % 2 != 0 {
+        return Err(cursor
+            .error("an even number of seeds, as part two reads them as start and length pairs"));
+    }
+
+    // Spans the current map hasn't moved yet, and the ones it has.
+    let mut unmapped: Vec<Span> = match part {
+        Part::One => seeds
+            .iter()
+            .map(|&start| Span { start, length: 1 })
+            .collect(),
+        Part::Two => seeds
+            .chunks_exact(2)
+            .map(|pair| Span {
+                start: pair[0],
+                length: pair[1],
+            })
+            .collect(),
+    };
+    let mut mapped = Vec::new();
+    let mut current = b"seed".to_vec();
+    let mut expect = Expect::Blank;
+    let mut last = number;
+    while let Some((number, line)) = lines.next_line() {
+        last = number;
+        let mut cursor = Cursor::new(number, line);
+        expect = match (expect, line.is_empty()) {
+            (Expect::Blank | Expect::Entry, true) => {
+                unmapped.append(&mut mapped);
+                Expect::Header
+            }
+            (Expect::Blank, false) => return Err(cursor.error("a blank line")),
+            (Expect::Header, _) => {
+                let (from, to) = parse_header(&mut cursor)?;
+                if from != current {
+                    let current = String::from_utf8_lossy(&current);
+                    return Err(Cursor::new(number, line).error(format!(
+                        "a map from `{current}`, as maps must be listed in the order they're \
+                         followed to stream them"
+                    )));
+                }
+                current = to.to_vec();
+                Expect::FirstEntry
+            }
+            (Expect::FirstEntry | Expect::Entry, _) => {
+                let entry = parse_entry(&mut cursor)?;
+                let mut rest = Vec::with_capacity(unmapped.len());
+                for span in unmapped.drain(..) {
+                    let (unaffected, affected) = entry.apply(&span);
+                    rest.extend(unaffected);
+                    mapped.extend(affected);
+                }
+                unmapped = rest;
+                Expect::Entry
+            }
+        };
+    }
+    match expect {
+        Expect::Blank => return Err(ParseError::in_line(last + 1, b"", 0, "a blank line")),
+        Expect::Header | Expect::FirstEntry => {
+            return Err(ParseError::in_line(last + 1, b"", 0, "a map"))
+        }
+        Expect::Entry => unmapped.append(&mut mapped),
+    }
+    if current != DEST.as_bytes() {
+        let current = String::from_utf8_lossy(&current);
+        let expected = format!("a map from `{current}`");
+        return Err(ParseError::in_line(last + 1, b"", 0, expected));
+    }
+    Ok(unmapped
+        .iter()
+        .map(|span| span.start)
+        .min()
+        .expect("the seeds line has at least one pair")
+        .into())
+}
@@ -1,12 +1,8 @@
-use std::{env::args, fs};
+use std::process::ExitCode;
 
-use day06::{parse, Race};
+use common::Part;
+use day06::Day06;
 
-fn main() {
-    let input = fs::read_to_string(args().nth(1).unwrap()).unwrap();
-    let (_, races) = parse(&input).unwrap();
-
-    let result: i64 = races.iter().map(Race::ways_to_win).product();
-
-    dbg!(result);
+fn main() -> ExitCode {
+    common::main::<Day06>(Part::One)
 }
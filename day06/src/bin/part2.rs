@@ -1,24 +1,8 @@
-use std::{env::args, fs};
+use std::process::ExitCode;
 
-use day06::{parse, Race};
+use common::Part;
+use day06::Day06;
 
-fn concat_digits(digits: impl Iterator<Item = i64>) -> i64 {
-    digits
-        .map(|digit| digit.to_string())
-        .collect::<Vec<String>>()
-        .join("")
-        .parse()
-        .unwrap()
-}
-
-fn main() {
-    let input = fs::read_to_string(args().nth(1).unwrap()).unwrap();
-    let (_, races) = parse(&input).unwrap();
-
-    let race = Race {
-        time: concat_digits(races.iter().map(|race| race.time)),
-        distance: concat_digits(races.iter().map(|race| race.distance)),
-    };
-
-    dbg!(race.ways_to_win());
+fn main() -> ExitCode {
+    common::main::<Day06>(Part::Two)
 }
@@ -0,0 +1,86 @@
+use common::rng::Rng;
+
+use crate::Race;
+
+fn best_distance(time: i64) -> i64 {
+    (time / 2) * (time - time / 2)
+}
+
+/// Whether the race made by joining the races' digits can be won, as part 2 needs.
+fn joined_is_winnable(races: &[Race]) -> bool {
+    let joined = |field: fn(&Race) -> i64| {
+        races
+            .iter()
+            .map(|race| field(race).to_string())
+            .collect::<String>()
+            .parse::<i64>()
+            .unwrap()
+    };
+    joined(|race| race.distance) < best_distance(joined(|race| race.time))
+}
+
+/// The races in the puzzle's input format, in columns.
+fn format_races(races: &[Race]) -> String {
+    let width = |race: &Race| race.time.max(race.distance).to_string().len();
+    let line = |label: &str, field: fn(&Race) -> i64| {
+        let columns = races
+            .iter()
+            .map(|race| format!("{:>1$}", field(race), width(race)))
+            .collect::<Vec<_>>();
+        format!("{label:<9} {}\n", columns.join("  "))
+    };
+    line("Time:", |race| race.time) + &line("Distance:", |race| race.distance)
+}
+
+/// Up to four races (more would overflow part 2's joined-up race), each of which can be won, as
+/// can the race made by joining their digits.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = size.clamp(1, 4);
+    loop {
+        let races = (0..count)
+            .map(|_| {
+                let time = rng.range(7..=99);
+                let best = best_distance(time);
+                Race {
+                    time,
+                    distance: rng.range(best / 3..=best - 1),
+                }
+            })
+            .collect::<Vec<_>>();
+        if joined_is_winnable(&races) {
+            return format_races(&races);
+        }
+    }
+}
+
+/// The numbers with as many digits as `n`.
+fn same_digits(n: i64) -> (i64, i64) {
+    let digits = n.max(1).ilog10();
+    let low = if digits == 0 { 0 } else { 10i64.pow(digits) };
+    (low, 10i64.pow(digits + 1) - 1)
+}
+
+/// As many races as `races`, each with a time and record distance of as many digits as the one
+/// in its place, so that the joined-up race is as big. Every race can still be won.
+pub fn lookalike(races: &[Race], rng: &mut Rng) -> String {
+    loop {
+        let new = races
+            .iter()
+            .map(|race| loop {
+                let (low, high) = same_digits(race.time);
+                let time = rng.range(low..=high);
+                let (low, high) = same_digits(race.distance);
+                let high = high.min(best_distance(time) - 1);
+                if low <= high {
+                    break Race {
+                        time,
+                        distance: rng.range(low..=high),
+                    };
+                }
+            })
+            .collect::<Vec<_>>();
+        if joined_is_winnable(&new) {
+            return format_races(&new);
+        }
+    }
+}
@@ -1,17 +1,38 @@
-use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, space1},
-    multi::separated_list1,
-    IResult,
+use std::cmp::Ordering;
+
+use common::{
+    bytes::Lines,
+    differential::Check,
+    parse::{self, finish, IResult, NomError},
+    rng::Rng,
+    trace, Answer, ParseError, Part, Solution,
 };
+use nom::{bytes::complete::tag, character::complete::digit1, error::context};
+
+mod generate;
+pub mod reference;
+mod stream;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Race {
     pub time: i64,
     pub distance: i64,
 }
 
 impl Race {
+    /// [`Race::ways_to_win`], traced.
+    fn traced_ways_to_win(&self) -> i64 {
+        let ways = self.ways_to_win();
+        trace::info("race", || {
+            vec![
+                ("time", self.time.into()),
+                ("distance", self.distance.into()),
+                ("ways_to_win", ways.into()),
+            ]
+        });
+        ways
+    }
+
     pub fn ways_to_win(&self) -> i64 {
         // Find two points where this is true:
         // (race.time - hold_time) * hold_time == race.distance
@@ -33,22 +54,207 @@ impl Race {
     }
 }
 
-pub fn parse(input: &str) -> IResult<&str, Vec<Race>> {
-    let (input, _) = tag("Time:")(input)?;
-    let (input, _) = space1(input)?;
-    let (input, times) = separated_list1(space1, digit1)(input)?;
-    let (input, _) = tag("\nDistance:")(input)?;
-    let (input, _) = space1(input)?;
-    let (input, distances) = separated_list1(space1, digit1)(input)?;
+/// A number without a sign, since part two reads the digits of every number as one number.
+fn unsigned(input: &str) -> IResult<'_, i64> {
+    let (rest, digits) = digit1(input)?;
+    match digits.parse() {
+        Ok(number) => Ok((rest, number)),
+        Err(_) => Err(nom::Err::Error(NomError {
+            input,
+            expected: "a smaller number".into(),
+        })),
+    }
+}
+
+/// The times and distances, which should be the same length.
+fn parse_columns(input: &str) -> IResult<'_, (Vec<i64>, Vec<i64>)> {
+    let (input, times) = parse::labelled("Time", parse::numbers(unsigned))(input)?;
+    let (input, _) = context("`Distance:` on the next line", tag("\nDistance:"))(input)?;
+    let (input, distances) = parse::padded(parse::numbers(unsigned))(input)?;
+    Ok((input, (times, distances)))
+}
 
-    let races = times
+fn races(times: Vec<i64>, distances: Vec<i64>) -> Vec<Race> {
+    times
         .into_iter()
         .zip(distances)
-        .map(|(time, distance)| Race {
-            time: time.parse().unwrap(),
-            distance: distance.parse().unwrap(),
-        })
-        .collect();
+        .map(|(time, distance)| Race { time, distance })
+        .collect()
+}
+
+pub fn parse(input: &str) -> IResult<'_, Vec<Race>> {
+    let (input, (times, distances)) = parse_columns(input)?;
+    Ok((input, races(times, distances)))
+}
+
+/// `left` and `right` written one after the other, as part two reads the kerning, or `None` if
+/// that doesn't fit in an `i64`.
+fn join(left: i64, right: i64) -> Option<i64> {
+    let digits = right.checked_ilog10().unwrap_or(0) + 1;
+    left.checked_mul(10i64.checked_pow(digits)?)?
+        .checked_add(right)
+}
+
+const JOINED_TOO_LONG: &str = "numbers that still fit in 64 bits when joined for part two";
+
+/// An error at the end of the line whose numbers don't fit in an `i64` once they're joined up
+/// for part two, if either doesn't. Part two can't fail, so this is checked while parsing.
+fn check_joined(input: &str, times: &[i64], distances: &[i64]) -> Option<ParseError> {
+    let fits = |values: &[i64]| {
+        values
+            .iter()
+            .try_fold(0, |joined, &n| join(joined, n))
+            .is_some()
+    };
+    let (time_line, distance_line) = input.split_once('\n')?;
+    let line = match (fits(times), fits(distances)) {
+        (false, _) => time_line,
+        (_, false) => distance_line,
+        _ => return None,
+    };
+    Some(ParseError::new(input, &line[line.len()..], JOINED_TOO_LONG))
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input<'a> = Vec<Race>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (times, distances) = finish(input, parse_columns(input))?;
+        match check_joined(input, &times, &distances) {
+            Some(error) => Err(error),
+            None => Ok(races(times, distances)),
+        }
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let (times, distances) = match finish(input, parse_columns(input)) {
+            Ok(columns) => columns,
+            Err(error) => return vec![error],
+        };
+
+        // The races are paired up by position, so an extra number would be silently dropped.
+        let (time_line, distance_line) = input.split_once('\n').unwrap();
+        let mut problems: Vec<_> = check_joined(input, &times, &distances)
+            .into_iter()
+            .collect();
+        match times.len().cmp(&distances.len()) {
+            Ordering::Equal => {}
+            Ordering::Less => problems.push(ParseError::new(
+                input,
+                &time_line[time_line.len()..],
+                format!("as many times as distances ({})", distances.len()),
+            )),
+            Ordering::Greater => problems.push(ParseError::new(
+                input,
+                &distance_line[distance_line.len()..],
+                format!("as many distances as times ({})", times.len()),
+            )),
+        }
+        problems
+    }
 
-    Ok((input, races))
+    fn part1(races: &Self::Input<'_>) -> Answer {
+        races
+            .iter()
+            .map(Race::traced_ways_to_win)
+            .product::<i64>()
+            .into()
+    }
+
+    fn part2(races: &Self::Input<'_>) -> Answer {
+        let race = races
+            .iter()
+            .try_fold(
+                Race {
+                    time: 0,
+                    distance: 0,
+                },
+                |joined, race| {
+                    Some(Race {
+                        time: join(joined.time, race.time)?,
+                        distance: join(joined.distance, race.distance)?,
+                    })
+                },
+            )
+            .expect("parsing checks that the joined race fits");
+
+        race.traced_ways_to_win().into()
+    }
+
+    fn differentials() -> Vec<Box<dyn Check>> {
+        reference::differentials()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(races: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(races, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::differential;
+
+    use super::*;
+
+    #[test]
+    fn test_matches_reference() {
+        differential::assert_all(Day06::differentials());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Day06::check("Time: 7 15\nDistance: 9 40"), vec![]);
+        let problems = Day06::check("Time: 7 15 30\nDistance: 9 40");
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| (p.line, p.column, p.expected.as_str()))
+                .collect::<Vec<_>>(),
+            [(2, 15, "as many distances as times (3)")]
+        );
+
+        let problems = Day06::check("Time: 7 -3\nDistance: 9 40");
+        assert_eq!(problems.len(), 1);
+        assert_eq!((problems[0].line, problems[0].column), (1, 9));
+        let ten_races = format!("Time: {}\nDistance: 9", ["1000000"; 10].join(" "));
+        let problems = Day06::check(&ten_races);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| (p.line, p.expected.as_str()))
+                .collect::<Vec<_>>(),
+            [(1, JOINED_TOO_LONG), (2, "as many distances as times (10)")]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_what_part_two_cannot_join() {
+        let ten_races = format!("Time: {}\nDistance: 9", ["1000000"; 10].join(" "));
+        for input in ["Time: 7 -3\nDistance: 9 40", ten_races.as_str()] {
+            let error = Day06::parse(input).unwrap_err();
+            for part in [Part::One, Part::Two] {
+                let streamed = Day06::stream(part, &mut Lines::from_bytes(input)).unwrap();
+                let streamed = streamed.unwrap_err();
+                assert_eq!(
+                    (streamed.line, streamed.column),
+                    (error.line, error.column),
+                    "{input:?}"
+                );
+            }
+        }
+        assert_eq!(
+            Day06::parse(&ten_races).unwrap_err().expected,
+            JOINED_TOO_LONG
+        );
+    }
 }
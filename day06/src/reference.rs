@@ -0,0 +1,49 @@
+//! Slow but obviously correct versions of this day's solvers, for differential testing.
+
+use common::{
+    differential::{shrink_int, Check, Differential},
+    rng::Rng,
+};
+
+use crate::Race;
+
+/// Tries every hold time.
+pub fn ways_to_win(race: &Race) -> i64 {
+    (0..=race.time)
+        .filter(|hold| (race.time - hold) * hold > race.distance)
+        .count() as i64
+}
+
+/// A race that can be won, as every race in a puzzle input can.
+fn generate(rng: &mut Rng) -> Race {
+    let time = rng.range(2..=100);
+    let best = (time / 2) * (time - time / 2);
+    Race {
+        time,
+        distance: rng.range(0..=best - 1),
+    }
+}
+
+fn shrink(race: &Race) -> Vec<Race> {
+    let winnable = |race: &Race| {
+        let best = (race.time / 2) * (race.time - race.time / 2);
+        race.distance < best
+    };
+    let shorter = shrink_int(race.time, 2)
+        .into_iter()
+        .map(|time| Race { time, ..*race });
+    let closer = shrink_int(race.distance, 0)
+        .into_iter()
+        .map(|distance| Race { distance, ..*race });
+    shorter.chain(closer).filter(winnable).collect()
+}
+
+pub fn differentials() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Differential {
+        name: "day06 Race::ways_to_win",
+        generate,
+        shrink,
+        fast: Race::ways_to_win,
+        reference: ways_to_win,
+    })]
+}
@@ -0,0 +1,92 @@
+//! Solving over bytes, for inputs too big to read in whole. Races are paired up by position, so
+//! the times are kept until the distances come, as numbers rather than text.
+
+use common::{
+    bytes::{Cursor, Lines},
+    Answer, ParseError, Part,
+};
+
+use crate::{join, Race, JOINED_TOO_LONG};
+
+/// Passes each number after `label` on the next line to `each`, failing with `expected` if it's
+/// not there, and returns the line's number. `missing` is the number the line would have. If the
+/// numbers don't fit in 64 bits once joined, the error for that comes back too, to be reported
+/// after anything wrong with the lines' text, as the text parser does.
+fn parse_row(
+    lines: &mut Lines<'_>,
+    missing: usize,
+    label: &str,
+    expected: &str,
+    mut each: impl FnMut(i64),
+) -> Result<(usize, Option<ParseError>), ParseError> {
+    let (number, line) = lines.next_line().unwrap_or((missing, b""));
+    let mut cursor = Cursor::new(number, line);
+    if !cursor.eat(label) {
+        return Err(cursor.error(expected));
+    }
+    let mut count = 0;
+    let mut joined = Some(0);
+    loop {
+        cursor.spaces();
+        if cursor.is_empty() {
+            break;
+        }
+        let start = cursor.clone();
+        let value =
+            i64::try_from(cursor.unsigned()?).map_err(|_| start.error("a smaller number"))?;
+        joined = joined.and_then(|joined| join(joined, value));
+        each(value);
+        count += 1;
+    }
+    match (count, joined) {
+        (0, _) => Err(cursor.error("a number")),
+        (_, None) => Ok((number, Some(cursor.error(JOINED_TOO_LONG)))),
+        _ => Ok((number, None)),
+    }
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let mut times = Vec::new();
+    let (time_line, times_too_long) =
+        parse_row(lines, 1, "Time:", "`Time:`", |time| times.push(time))?;
+
+    let mut races = times.iter();
+    let mut product = 1i64;
+    let mut joined = Race {
+        time: 0,
+        distance: 0,
+    };
+    let (_, distances_too_long) = parse_row(
+        lines,
+        time_line + 1,
+        "Distance:",
+        "`Distance:` on the next line",
+        |distance| {
+            // Extra distances have no time to race against, as with the text parser.
+            let Some(&time) = races.next() else {
+                return;
+            };
+            match part {
+                Part::One => product *= Race { time, distance }.ways_to_win(),
+                // Both rows fit when joined whole, so the races that pair up do too.
+                Part::Two => {
+                    joined = Race {
+                        time: join(joined.time, time).unwrap_or(i64::MAX),
+                        distance: join(joined.distance, distance).unwrap_or(i64::MAX),
+                    }
+                }
+            }
+        },
+    )?;
+    if let Some((number, line)) = lines.next_line() {
+        return Err(ParseError::in_line(number, line, 0, "end of input"));
+    }
+    if let Some(error) = times_too_long.or(distances_too_long) {
+        return Err(error);
+    }
+
+    Ok(match part {
+        Part::One => product.into(),
+        Part::Two => joined.ways_to_win().into(),
+    })
+}
@@ -0,0 +1,56 @@
+use std::ops::RangeInclusive;
+
+use common::{hash::HashSet, rng::Rng};
+
+const CARDS: &[u8] = b"AKQJT98765432";
+
+/// `count` distinct Camel Cards hands, with bids in `bids`. Hands are built from a random shape
+/// (five of a kind down to high card) so every type turns up, and jokers are common.
+fn deal(rng: &mut Rng, count: usize, bids: RangeInclusive<i64>) -> String {
+    const SHAPES: [&[usize]; 7] = [
+        &[5],
+        &[4, 1],
+        &[3, 2],
+        &[3, 1, 1],
+        &[2, 2, 1],
+        &[2, 1, 1, 1],
+        &[1, 1, 1, 1, 1],
+    ];
+
+    let mut seen = HashSet::default();
+    let mut text = String::new();
+    // There are far more hands than any sensible size, but don't loop forever on a silly one.
+    while seen.len() < count.min(100_000) {
+        let mut labels = CARDS.to_vec();
+        rng.shuffle(&mut labels);
+        if rng.chance(0.3) {
+            let joker = labels.iter().position(|&c| c == b'J').unwrap();
+            labels.swap(0, joker);
+        }
+        let mut hand = rng
+            .choose(&SHAPES)
+            .iter()
+            .zip(labels)
+            .flat_map(|(&count, label)| std::iter::repeat_n(label, count))
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut hand);
+
+        let hand = String::from_utf8(hand).unwrap();
+        if seen.insert(hand.clone()) {
+            text += &format!("{hand} {}\n", rng.range(bids.clone()));
+        }
+    }
+    text
+}
+
+/// Distinct hands with bids from 1 to 1000.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    deal(rng, size, 1..=1000)
+}
+
+/// As many hands as `hands`, with bids in the same range.
+pub fn lookalike(hands: &[(&str, i32)], rng: &mut Rng) -> String {
+    let bids = hands.iter().map(|&(_, bid)| i64::from(bid));
+    let bids = bids.clone().min().unwrap_or(1)..=bids.max().unwrap_or(1);
+    deal(rng, hands.len(), bids)
+}
@@ -1,3 +1,94 @@
+use common::{
+    bytes::Lines,
+    hash::HashSet,
+    parse::{self, IResult},
+    rng::Rng,
+    Answer, ParseError, Part, Solution,
+};
+use nom::{
+    bytes::complete::take_while_m_n,
+    character::complete::{self, space1},
+    error::context,
+    sequence::separated_pair,
+};
+
+mod generate;
+mod part1;
+mod part2;
+mod stream;
+
 pub fn array_to_index_map<T, const N: usize>(items: [T; N]) -> impl Iterator<Item = (T, usize)> {
     items.into_iter().rev().enumerate().map(|(i, x)| (x, i))
 }
+
+fn parse_hand(input: &str) -> IResult<'_, (&str, i32)> {
+    separated_pair(
+        context(
+            "five cards from `AKQJT98765432`",
+            take_while_m_n(5, 5, |c| "AKQJT98765432".contains(c)),
+        ),
+        space1,
+        complete::i32,
+    )(input)
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input<'a> = Vec<(&'a str, i32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse::lines(input, parse_hand)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let hands = match parse::check_lines(input, input, parse_hand) {
+            Ok(hands) => hands,
+            Err(errors) => return errors,
+        };
+
+        // Equal hands would tie, leaving their ranks up to the sort.
+        let mut seen = HashSet::default();
+        hands
+            .into_iter()
+            .filter(|(cards, _)| !seen.insert(*cards))
+            .map(|(cards, _)| ParseError::new(input, cards, format!("`{cards}` only once")))
+            .collect()
+    }
+
+    fn part1(hands: &Self::Input<'_>) -> Answer {
+        part1::solve(hands).into()
+    }
+
+    fn part2(hands: &Self::Input<'_>) -> Answer {
+        part2::solve(hands).into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(hands: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(hands, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let error = Day07::parse("32T3K 765\nT5XJ5 684").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "five cards from `AKQJT98765432`");
+
+        let error = Day07::parse("32T3K 765\nT55J5 -").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "a number");
+    }
+}
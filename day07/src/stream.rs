@@ -0,0 +1,106 @@
+//! Solving over bytes, for inputs too big to read in whole. Ranks depend on every hand, but there
+//! are only 13^5 different hands, so rather than keeping each hand, its bid is added to a tally
+//! for its cards. The tallies take the same memory however many hands there are.
+
+use common::{
+    bytes::{Cursor, Lines},
+    Answer, ParseError, Part,
+};
+
+const CARDS: usize = 13;
+const HANDS: usize = CARDS.pow(5);
+
+/// The cards from weakest to strongest.
+fn card_order(part: Part) -> &'static [u8; CARDS] {
+    match part {
+        Part::One => b"23456789TJQKA",
+        Part::Two => b"J23456789TQKA",
+    }
+}
+
+/// The hand's type, from 0 for high card to 6 for five of a kind, counting jokers as whatever
+/// card helps most in part two.
+fn hand_type(part: Part, cards: [usize; 5]) -> u8 {
+    let mut counts = [0u8; CARDS];
+    for card in cards {
+        counts[card] += 1;
+    }
+    let jokers = match part {
+        Part::One => 0,
+        // Jokers are the weakest card in part two.
+        Part::Two => std::mem::take(&mut counts[0]),
+    };
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    match (counts[0] + jokers, counts[1]) {
+        (5, _) => 6,
+        (4, _) => 5,
+        (3, 2) => 4,
+        (3, _) => 3,
+        (2, 2) => 2,
+        (2, _) => 1,
+        _ => 0,
+    }
+}
+
+/// The bids made on one hand, tallied so that ranks can be handed out once every hand is in.
+/// Equal hands keep the order they came in, so the `k`th of them gets the `k`th rank of theirs.
+#[derive(Debug, Clone, Copy, Default)]
+struct Tally {
+    count: u32,
+    bids: i64,
+    /// The sum of each bid times the number of equal hands before it.
+    weighted: i64,
+}
+
+/// A hand's cards as numbers from 0 for the weakest card, if it's five cards.
+fn parse_cards(order: &[u8; CARDS], cards: &[u8]) -> Option<[usize; 5]> {
+    let mut parsed = [0; 5];
+    for (parsed, card) in parsed.iter_mut().zip(cards) {
+        *parsed = order.iter().position(|c| c == card)?;
+    }
+    Some(parsed)
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let order = card_order(part);
+    // Indexed by the cards read as a base 13 number, so hands of the same type are in order.
+    let mut tallies = vec![Tally::default(); HANDS];
+    while let Some((number, line)) = lines.next_line() {
+        let mut cursor = Cursor::new(number, line);
+        let cards = cursor
+            .take(5)
+            .and_then(|cards| parse_cards(order, cards))
+            .ok_or_else(|| cursor.error("five cards from `AKQJT98765432`"))?;
+        if !cursor.spaces() {
+            return Err(cursor.error("a space"));
+        }
+        let bid = cursor.signed()?;
+        cursor.end()?;
+
+        let tally = &mut tallies[cards.iter().fold(0, |hand, card| hand * CARDS + card)];
+        tally.weighted += i64::from(tally.count) * bid;
+        tally.count += 1;
+        tally.bids += bid;
+    }
+
+    let mut by_type = [const { Vec::new() }; 7];
+    for (hand, tally) in tallies.iter().enumerate() {
+        if tally.count > 0 {
+            let mut cards = [0; 5];
+            let mut rest = hand;
+            for card in cards.iter_mut().rev() {
+                *card = rest % CARDS;
+                rest /= CARDS;
+            }
+            by_type[usize::from(hand_type(part, cards))].push(tally);
+        }
+    }
+
+    let mut ranked = 0i64;
+    let mut winnings = 0i64;
+    for tally in by_type.iter().flatten() {
+        winnings += (ranked + 1) * tally.bids + tally.weighted;
+        ranked += i64::from(tally.count);
+    }
+    Ok(winnings.into())
+}
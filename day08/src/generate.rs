@@ -0,0 +1,228 @@
+use common::{
+    hash::{HashMap, HashSet},
+    rng::Rng,
+};
+use itertools::Itertools;
+
+use crate::{Network, Node};
+
+const PRIMES: [usize; 6] = [2, 3, 5, 7, 11, 13];
+
+/// A random three-letter name that isn't taken, and doesn't end in `A` or `Z` unless asked to.
+fn name(rng: &mut Rng, taken: &mut HashSet<String>, last: Option<char>) -> String {
+    loop {
+        let mut name = (0..2)
+            .map(|_| char::from(b'A' + rng.below(26) as u8))
+            .collect::<String>();
+        name.push(last.unwrap_or_else(|| char::from(b'B' + rng.below(24) as u8)));
+        if taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// A network with an L/R path of `size` steps (up to 100) and a few ghosts, built the way the
+/// real inputs are: each ghost's `..A` start leads into a cycle through its `..Z` end that's the
+/// path's length times a distinct prime, so it reaches the end after exactly one cycle and
+/// every cycle after. The first ghost starts at `AAA` and ends at `ZZZ`, for part 1.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let path = (0..size.clamp(1, 100))
+        .map(|_| if rng.chance(0.5) { 'L' } else { 'R' })
+        .collect::<String>();
+
+    let mut primes = PRIMES;
+    rng.shuffle(&mut primes);
+    let ghosts = rng.range(1..=6) as usize;
+
+    let mut taken = HashSet::from_iter(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut nodes = Vec::new();
+    for (ghost, &prime) in primes[..ghosts].iter().enumerate() {
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (
+                name(rng, &mut taken, Some('A')),
+                name(rng, &mut taken, Some('Z')),
+            ),
+        };
+
+        let length = path.len() * prime;
+        let cycle = std::iter::once(end)
+            .chain((1..length).map(|_| name(rng, &mut taken, None)))
+            .collect::<Vec<_>>();
+        let children = (0..length)
+            .map(|i| {
+                let next = cycle[(i + 1) % length].clone();
+                // The other branch is never taken, since every visit to this node is on the same
+                // step of the path.
+                let other = rng.choose(&cycle).clone();
+                match path.as_bytes()[i % path.len()] {
+                    b'L' => (next, other),
+                    _ => (other, next),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        nodes.push((start, children[0].clone()));
+        nodes.extend(cycle.into_iter().zip(children));
+    }
+    rng.shuffle(&mut nodes);
+
+    let nodes = nodes
+        .into_iter()
+        .map(|(name, (left, right))| format!("{name} = ({left}, {right})\n"))
+        .collect::<String>();
+    format!("{path}\n\n{nodes}")
+}
+
+/// How many nodes can be reached from `start`, including itself.
+fn reachable(nodes: &HashMap<&str, Node>, start: &str) -> usize {
+    let mut seen = HashSet::from_iter([start]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop().and_then(|name| nodes.get(name)) {
+        for next in [node.left, node.right] {
+            if seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    seen.len()
+}
+
+/// The length of the shortest suffix of `path` that doesn't end anywhere else in it, reading it
+/// as a loop, or `None` if there isn't one, as in `LRLR`.
+fn unique_suffix(path: &[u8]) -> Option<usize> {
+    let n = path.len();
+    (1..=n)
+        .find(|&m| (0..n - 1).all(|end| (0..m).any(|k| path[(end + n - k) % n] != path[n - 1 - k])))
+}
+
+/// The two nodes that a node leads to on its way into `level` of a ghost's ladder (see
+/// [`ladder`]), as `(left, right)`. `on_track` is whether the node is the second of its pair.
+fn children(
+    rng: &mut Rng,
+    levels: &[[String; 2]],
+    level: usize,
+    on_track: bool,
+    path: &[u8],
+    suffix: usize,
+) -> (String, String) {
+    let [off, on] = &levels[level];
+    let first_matching = levels.len() - suffix;
+    if level < first_matching {
+        return match rng.chance(0.5) {
+            true => (off.clone(), on.clone()),
+            false => (on.clone(), off.clone()),
+        };
+    }
+    if level > first_matching && !on_track {
+        return (off.clone(), off.clone());
+    }
+    match path[path.len() - suffix + level - first_matching] {
+        b'L' => (on.clone(), off.clone()),
+        _ => (off.clone(), on.clone()),
+    }
+}
+
+/// A ghost's nodes, shaped like the real inputs': its start, then `levels` pairs of nodes, where
+/// every step moves one level along whichever way it goes, looping back round after the last.
+/// Over the last `suffix` levels, the second node of each pair can only be reached by steps
+/// matching the end of `path`, and the last pair's second node is the ghost's end. Since that
+/// stretch of `path` only turns up at its end, the end is reached after `levels` steps only when
+/// the path starts over too.
+fn ladder(
+    rng: &mut Rng,
+    taken: &mut HashSet<String>,
+    (start, end): (String, String),
+    levels: usize,
+    path: &[u8],
+    suffix: usize,
+) -> Vec<(String, (String, String))> {
+    let levels = (0..levels)
+        .map(|level| {
+            let off = name(rng, taken, None);
+            match level == levels - 1 {
+                true => [off, end.clone()],
+                false => [off, name(rng, taken, None)],
+            }
+        })
+        .collect_vec();
+
+    let mut nodes = vec![(start, children(rng, &levels, 0, true, path, suffix))];
+    for (level, pair) in levels.iter().enumerate() {
+        let next = (level + 1) % levels.len();
+        for (track, node) in pair.iter().enumerate() {
+            let children = children(rng, &levels, next, track == 1, path, suffix);
+            nodes.push((node.clone(), children));
+        }
+    }
+    nodes
+}
+
+/// A network with a fresh path as long as `network`'s and as many ghosts, which have as many
+/// nodes between them as `network`'s do. Like in the real inputs, each ghost reaches its end
+/// after a whole number of trips along the path, and again every time after that.
+pub fn lookalike(network: &Network, rng: &mut Rng) -> String {
+    let starts = network
+        .nodes
+        .values()
+        .filter(|node| node.is_start())
+        .map(|node| node.name)
+        .sorted()
+        .collect_vec();
+    // A ghost's start and the pairs of nodes it loops through.
+    let levels = starts
+        .iter()
+        .map(|start| ((reachable(&network.nodes, start) - 1) / 2).max(1))
+        .collect_vec();
+    let shortest = levels.iter().copied().min().unwrap_or(1);
+
+    // The path's unique ending has to fit in the shortest ghost. Tiny examples may not have a
+    // path with one that short, and then get longer ghosts.
+    let mut tries = 0;
+    let (path, suffix) = loop {
+        let path = (0..network.path.len().max(1))
+            .map(|_| if rng.chance(0.5) { b'L' } else { b'R' })
+            .collect_vec();
+        tries += 1;
+        match unique_suffix(&path) {
+            Some(suffix) if suffix <= shortest || tries >= 1000 => break (path, suffix),
+            _ => {}
+        }
+    };
+
+    // Moves levels between ghosts, so they reach their ends at different times than
+    // `network`'s do but have as many nodes between them.
+    let mut levels = levels
+        .into_iter()
+        .map(|levels| levels.max(suffix))
+        .collect_vec();
+    let lowest = shortest.max(suffix);
+    let highest = levels.iter().copied().max().unwrap_or(1);
+    for _ in 0..4 * levels.len() {
+        let (from, to) = (rng.index(levels.len()), rng.index(levels.len()));
+        let spare = (levels[from] - lowest).min(highest - levels[to]);
+        let moved = rng.range(0..=spare as i64) as usize;
+        levels[from] -= moved;
+        levels[to] += moved;
+    }
+
+    let mut taken = HashSet::from_iter(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut nodes = Vec::new();
+    for (ghost, start) in starts.iter().enumerate() {
+        let ends = match (ghost, *start) {
+            (0, "AAA") => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (
+                name(rng, &mut taken, Some('A')),
+                name(rng, &mut taken, Some('Z')),
+            ),
+        };
+        nodes.extend(ladder(rng, &mut taken, ends, levels[ghost], &path, suffix));
+    }
+    rng.shuffle(&mut nodes);
+
+    let nodes = nodes
+        .into_iter()
+        .map(|(name, (left, right))| format!("{name} = ({left}, {right})\n"))
+        .collect::<String>();
+    format!("{}\n\n{nodes}", String::from_utf8(path).unwrap())
+}
@@ -0,0 +1,371 @@
+use common::{
+    budget,
+    bytes::Lines,
+    hash::{HashMap, HashSet},
+    parse::{self, finish, IResult},
+    rng::Rng,
+    trace, Answer, Extras, ParseError, Part, Solution,
+};
+use itertools::Itertools;
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::alphanumeric1,
+    combinator::cut,
+    error::context,
+    multi::{many1, separated_list1},
+    Parser,
+};
+
+mod generate;
+mod stream;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn parse(input: &str) -> IResult<'_, Direction> {
+        let (input, direction) = context("`L` or `R`", alt((tag("L"), tag("R"))))
+            .map(|c| match c {
+                "L" => Direction::Left,
+                "R" => Direction::Right,
+                _ => unreachable!(),
+            })
+            .parse(input)?;
+        Ok((input, direction))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Node<'a> {
+    name: &'a str,
+    left: &'a str,
+    right: &'a str,
+}
+
+impl Node<'_> {
+    fn parse(input: &str) -> IResult<'_, Node<'_>> {
+        let (input, (name, (left, right))) = parse::record(alphanumeric1, alphanumeric1)(input)?;
+
+        Ok((input, Node { name, left, right }))
+    }
+
+    fn is_start(&self) -> bool {
+        self.name.ends_with('A')
+    }
+
+    fn is_end(&self) -> bool {
+        self.name.ends_with('Z')
+    }
+}
+
+fn parse(input: &str) -> IResult<'_, (Vec<Direction>, Vec<Node<'_>>)> {
+    let (input, path) = many1(Direction::parse)(input)?;
+    let (input, _) = parse::blank_line(input)?;
+    let (input, nodes) = separated_list1(tag("\n"), cut(Node::parse))(input)?;
+
+    Ok((input, (path, nodes)))
+}
+
+pub fn lowest_common_multiple(mut items: Vec<usize>) -> usize {
+    let mut divisors: Vec<usize> = Vec::new();
+
+    loop {
+        if items.iter().all(|&x| x == 1) {
+            break;
+        }
+        let lowest_divisor = (2..)
+            .find(|&divisor| items.iter().any(|x| x % divisor == 0))
+            .unwrap();
+        divisors.push(lowest_divisor);
+
+        for x in items.iter_mut() {
+            if *x % lowest_divisor == 0 {
+                *x /= lowest_divisor;
+            }
+        }
+    }
+    divisors.into_iter().product()
+}
+
+/// The error for a reference to `name`, which isn't a node.
+fn missing_node(input: &str, name: &str) -> ParseError {
+    let expected = format!("the name of a node, but there's no `{name}`");
+    ParseError::new(input, name, expected)
+}
+
+/// A network whose nodes only point at nodes in it.
+pub struct Network<'a> {
+    path: Vec<Direction>,
+    nodes: HashMap<&'a str, Node<'a>>,
+}
+
+/// The node one step from `current` in direction `dir`, which is always there since parsing
+/// turns away dangling names. Walks that never reach their end are only stopped by a [`budget`].
+fn step<'a>(nodes: &HashMap<&'a str, Node<'a>>, current: &str, dir: &Direction) -> &'a str {
+    budget::step();
+    let next = match dir {
+        Direction::Left => nodes[current].left,
+        Direction::Right => nodes[current].right,
+    };
+    trace::debug("step", || {
+        vec![
+            ("from", current.into()),
+            ("direction", format!("{dir:?}").into()),
+            ("to", next.into()),
+        ]
+    });
+    next
+}
+
+/// Number of steps each ghost takes from its `..A` start to reach a `..Z` node, in order of the
+/// starts' names.
+pub fn ghost_loop_sizes(network: &Network) -> Vec<usize> {
+    let Network { path, nodes } = network;
+
+    nodes
+        .values()
+        .filter(|node| node.is_start())
+        .sorted_by_key(|node| node.name)
+        .map(|start| {
+            let mut current = start.name;
+            let steps = path
+                .iter()
+                .cycle()
+                .take_while(|dir| {
+                    let arrived = nodes[current].is_end();
+                    if !arrived {
+                        current = step(nodes, current, dir);
+                    }
+                    !arrived
+                })
+                .count();
+            trace::info("ghost", || {
+                vec![
+                    ("start", start.name.into()),
+                    ("end", current.into()),
+                    ("steps", steps.into()),
+                ]
+            });
+            steps
+        })
+        .collect_vec()
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input<'a> = Network<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (path, nodes) = finish(input, parse(input))?;
+        let names = nodes.iter().map(|node| node.name).collect::<HashSet<_>>();
+        if let Some(name) = nodes
+            .iter()
+            .flat_map(|node| [node.left, node.right])
+            .find(|name| !names.contains(name))
+        {
+            return Err(missing_node(input, name));
+        }
+        let nodes = nodes.into_iter().map(|n| (n.name, n)).collect();
+
+        Ok(Network { path, nodes })
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let Some((path, nodes_text)) = input.split_once("\n\n") else {
+            return Self::parse(input).err().into_iter().collect();
+        };
+        let path = finish(input, many1(Direction::parse)(path));
+        let nodes = parse::check_lines(input, nodes_text, Node::parse);
+        let nodes = match (path, nodes) {
+            (Ok(_), Ok(nodes)) => nodes,
+            (path, nodes) => {
+                let mut errors = path.err().into_iter().collect::<Vec<_>>();
+                errors.extend(nodes.err().into_iter().flatten());
+                return errors;
+            }
+        };
+
+        let mut problems = Vec::new();
+        let mut names = HashSet::default();
+        for node in &nodes {
+            if !names.insert(node.name) {
+                let expected = format!("only one node named `{}`", node.name);
+                problems.push(ParseError::new(input, node.name, expected));
+            }
+        }
+        for name in nodes.iter().flat_map(|node| [node.left, node.right]) {
+            if !names.contains(name) {
+                problems.push(missing_node(input, name));
+            }
+        }
+        if !nodes.iter().any(Node::is_start) {
+            problems.push(ParseError::at_offset(
+                input,
+                input.len(),
+                "a node ending in `A` to start from",
+            ));
+        }
+        problems
+    }
+
+    fn check_part(text: &str, network: &Self::Input<'_>, part: Part) -> Result<(), ParseError> {
+        match part {
+            Part::One if !network.nodes.contains_key("AAA") => Err(ParseError::at_offset(
+                text,
+                text.len(),
+                "a node named `AAA`",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn part1(network: &Self::Input<'_>) -> Answer {
+        let Network { path, nodes } = network;
+        const START: &str = "AAA";
+        const END: &str = "ZZZ";
+
+        let mut current = START;
+        let mut count = 0;
+
+        for dir in path.iter().cycle() {
+            current = step(nodes, current, dir);
+            count += 1;
+
+            if current == END {
+                break;
+            }
+        }
+
+        trace::info("arrived", || {
+            vec![("node", END.into()), ("steps", count.into())]
+        });
+        Answer::from(count)
+    }
+
+    fn part2(network: &Self::Input<'_>) -> Answer {
+        lowest_common_multiple(ghost_loop_sizes(network)).into()
+    }
+
+    fn extras(network: &Self::Input<'_>, part: Part) -> Extras {
+        match part {
+            Part::One => Vec::new(),
+            Part::Two => vec![("loop_sizes", ghost_loop_sizes(network).into())],
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(network: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(network, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        budget::{Budget, Exceeded},
+        solve,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Node::parse("AAA = (BBB, CCC)"),
+            Ok((
+                "",
+                Node {
+                    name: "AAA",
+                    left: "BBB",
+                    right: "CCC",
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let problems = Day08::check("LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, CCC)\nBBB = (ZZZ, ZZZ)");
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| (p.line, p.column, p.expected.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (5, 1, "only one node named `BBB`"),
+                (3, 13, "the name of a node, but there's no `ZZZ`"),
+                (4, 13, "the name of a node, but there's no `CCC`"),
+                (5, 8, "the name of a node, but there's no `ZZZ`"),
+                (5, 13, "the name of a node, but there's no `ZZZ`"),
+            ]
+        );
+        assert_eq!(
+            Day08::check("L\n\nBBB = (BBB, BBB)")[0].expected,
+            "a node ending in `A` to start from"
+        );
+    }
+
+    #[test]
+    fn test_stream_rejects_undefined_nodes() {
+        let input = "LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)";
+        let error = Day08::stream(Part::One, &mut Lines::from_bytes(input))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (3, 13));
+        assert_eq!(error.expected, "the name of a node, but there's no `ZZZ`");
+    }
+
+    #[test]
+    fn test_bad_networks_are_parse_errors() {
+        for (part, input, expected) in [
+            (
+                Part::Two,
+                "LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)",
+                "the name of a node, but there's no `ZZZ`",
+            ),
+            (
+                Part::One,
+                "L\n\nBBA = (BBZ, BBZ)\nBBZ = (BBA, BBA)",
+                "a node named `AAA`",
+            ),
+        ] {
+            let error = solve::<Day08>(part, input).unwrap_err();
+            let streamed = Day08::stream(part, &mut Lines::from_bytes(input))
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(error.expected, expected);
+            assert_eq!(streamed.expected, expected);
+        }
+        assert!(solve::<Day08>(Part::Two, "L\n\nBBA = (BBZ, BBZ)\nBBZ = (BBA, BBA)").is_ok());
+    }
+
+    #[test]
+    fn test_unreachable_end_runs_out_of_budget() {
+        let input = "L\n\nAAA = (AAA, AAA)\nBBA = (BBA, BBB)\nBBB = (BBA, BBB)\nZZZ = (ZZZ, ZZZ)";
+        let network = Day08::parse(input).unwrap();
+        let budget = Budget {
+            steps: Some(1000),
+            ..Budget::default()
+        };
+        assert_eq!(
+            budget::run(&budget, || Day08::part1(&network)),
+            Err(Exceeded::Steps(1000))
+        );
+        assert_eq!(
+            budget::run(&budget, || Day08::part2(&network)),
+            Err(Exceeded::Steps(1000))
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Reading the network from bytes, for inputs too big to read in whole. The walks can go
+//! anywhere in it, so the whole network is kept, but as a table of node numbers rather than the
+//! input's text, and walked the same way as the parsed network.
+
+use common::{
+    budget,
+    bytes::{Cursor, Lines},
+    hash::HashMap,
+    Answer, ParseError, Part,
+};
+
+use crate::{lowest_common_multiple, Direction};
+
+/// The network with every name swapped for a number, given in the order the names come up.
+#[derive(Default)]
+struct Table {
+    numbers: HashMap<Box<[u8]>, usize>,
+    names: Vec<Box<[u8]>>,
+    /// Each node's left and right nodes, or `None` until the node's line has been read.
+    links: Vec<Option<[usize; 2]>>,
+    /// Where each name first came up, to point at if it's never defined.
+    first_seen: Vec<(usize, Box<[u8]>, usize)>,
+}
+
+impl Table {
+    /// The number of the name at `cursor`, which is skipped.
+    fn name(
+        &mut self,
+        number: usize,
+        line: &[u8],
+        cursor: &mut Cursor,
+    ) -> Result<usize, ParseError> {
+        let start = line.len() - cursor.rest().len();
+        let len = cursor
+            .rest()
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric())
+            .count();
+        if len == 0 {
+            return Err(cursor.error("a letter or digit"));
+        }
+        let name = cursor.take(len).expect("the name is in the line");
+        if let Some(&node) = self.numbers.get(name) {
+            return Ok(node);
+        }
+        let node = self.names.len();
+        self.numbers.insert(name.into(), node);
+        self.names.push(name.into());
+        self.links.push(None);
+        self.first_seen.push((number, line.into(), start));
+        Ok(node)
+    }
+
+    fn step(&self, node: usize, direction: &Direction) -> usize {
+        budget::step();
+        let [left, right] = self.links[node].expect("every node is defined");
+        match direction {
+            Direction::Left => left,
+            Direction::Right => right,
+        }
+    }
+}
+
+fn parse_path(number: usize, line: &[u8]) -> Result<Vec<Direction>, ParseError> {
+    let mut cursor = Cursor::new(number, line);
+    let mut path = Vec::new();
+    loop {
+        if cursor.eat("L") {
+            path.push(Direction::Left);
+        } else if cursor.eat("R") {
+            path.push(Direction::Right);
+        } else {
+            break;
+        }
+    }
+    match (path.is_empty(), cursor.is_empty()) {
+        (true, _) => Err(cursor.error("`L` or `R`")),
+        (false, false) => Err(cursor.error("a blank line")),
+        (false, true) => Ok(path),
+    }
+}
+
+fn parse_node(table: &mut Table, number: usize, line: &[u8]) -> Result<(), ParseError> {
+    let mut cursor = Cursor::new(number, line);
+    let node = table.name(number, line, &mut cursor)?;
+    cursor.expect(" = ")?;
+    cursor.expect("(")?;
+    let left = table.name(number, line, &mut cursor)?;
+    cursor.expect(", ")?;
+    let right = table.name(number, line, &mut cursor)?;
+    cursor.expect(")")?;
+    cursor.end()?;
+    table.links[node] = Some([left, right]);
+    Ok(())
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let (number, line) = lines.next_line().unwrap_or((1, b""));
+    let path = parse_path(number, line)?;
+    let (number, line) = lines.next_line().unwrap_or((number + 1, b""));
+    if !line.is_empty() {
+        return Err(ParseError::in_line(number, line, 0, "a blank line"));
+    }
+    let mut table = Table::default();
+    let mut last = number;
+    while let Some((number, line)) = lines.next_line() {
+        parse_node(&mut table, number, line)?;
+        last = number;
+    }
+    if table.names.is_empty() {
+        return Err(ParseError::in_line(last + 1, b"", 0, "a letter or digit"));
+    }
+    // The parsed network can't tell until a walk gets there, but here it's known up front.
+    if let Some(node) = table.links.iter().position(Option::is_none) {
+        let (number, line, offset) = &table.first_seen[node];
+        let name = String::from_utf8_lossy(&table.names[node]);
+        let expected = format!("the name of a node, but there's no `{name}`");
+        return Err(ParseError::in_line(*number, line, *offset, expected));
+    }
+
+    Ok(match part {
+        Part::One => {
+            let Some(&start) = table.numbers.get(&b"AAA"[..]) else {
+                return Err(ParseError::in_line(last + 1, b"", 0, "a node named `AAA`"));
+            };
+            let end = table.numbers.get(&b"ZZZ"[..]).copied();
+            let mut current = start;
+            let mut count = 0;
+            for direction in path.iter().cycle() {
+                current = table.step(current, direction);
+                count += 1;
+                if Some(current) == end {
+                    break;
+                }
+            }
+            count.into()
+        }
+        Part::Two => {
+            let ends_in = |node: usize, last: u8| table.names[node].last() == Some(&last);
+            let mut starts = (0..table.names.len())
+                .filter(|&node| ends_in(node, b'A'))
+                .collect::<Vec<_>>();
+            starts.sort_by_key(|&node| &table.names[node]);
+            let sizes = starts
+                .into_iter()
+                .map(|start| {
+                    let mut current = start;
+                    path.iter()
+                        .cycle()
+                        .take_while(|direction| {
+                            let arrived = ends_in(current, b'Z');
+                            if !arrived {
+                                current = table.step(current, direction);
+                            }
+                            !arrived
+                        })
+                        .count()
+                })
+                .collect();
+            lowest_common_multiple(sizes).into()
+        }
+    })
+}
@@ -0,0 +1,59 @@
+use common::rng::Rng;
+
+/// `count` readings sampled from the polynomial with the given coefficients of x choose k, which
+/// keep every reading a whole number.
+fn readings(coefficients: &[i64], count: usize) -> String {
+    let readings = (0..count as i64).map(|x| {
+        let mut choose = 1;
+        let mut value = 0;
+        for (k, coefficient) in coefficients.iter().enumerate() {
+            value += coefficient * choose;
+            choose = choose * (x - k as i64) / (k as i64 + 1);
+        }
+        value.to_string()
+    });
+    readings.collect::<Vec<_>>().join(" ") + "\n"
+}
+
+/// Sensor histories of 21 readings, each sampled from a random integer polynomial of degree at
+/// most 10, so the differences always reach zero before the readings run out.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let coefficients = (0..=rng.range(0..=10))
+                .map(|_| rng.range(-10..=10))
+                .collect::<Vec<_>>();
+            readings(&coefficients, 21)
+        })
+        .collect()
+}
+
+/// As many histories as `sensors`, each as long as the one it stands in for and from a polynomial
+/// of the same degree, with coefficients of the same signs and between half and all of the size.
+pub fn lookalike(sensors: &[Vec<i64>], rng: &mut Rng) -> String {
+    sensors
+        .iter()
+        .map(|sensor| {
+            // The first reading, then the first of each row of differences: the coefficients
+            // `sensor` was sampled from.
+            let mut coefficients = Vec::new();
+            let mut row = sensor.clone();
+            while !row.is_empty() {
+                coefficients.push(row[0]);
+                row = row.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            }
+            while coefficients.last() == Some(&0) {
+                coefficients.pop();
+            }
+
+            let coefficients = coefficients
+                .iter()
+                .map(|&coefficient| {
+                    let size = coefficient.abs();
+                    coefficient.signum() * rng.range((size + 1) / 2..=size)
+                })
+                .collect::<Vec<_>>();
+            readings(&coefficients, sensor.len())
+        })
+        .collect()
+}
@@ -1,14 +1,12 @@
+use common::{bytes::Lines, parse, rng::Rng, Answer, ParseError, Part, Solution};
 use itertools::Itertools;
+use nom::character::complete;
 
-pub fn parse_sensors(input: &str) -> Vec<Vec<i64>> {
-    input
-        .lines()
-        .map(|line| {
-            line.split(' ')
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect_vec()
-        })
-        .collect_vec()
+mod generate;
+mod stream;
+
+pub fn parse_sensors(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    parse::lines(input, parse::padded(parse::numbers(complete::i64)))
 }
 
 pub fn process_sensor(
@@ -35,3 +33,62 @@ pub fn process_sensor(
 
     firsts.iter().rev().fold(0, fold)
 }
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input<'a> = Vec<Vec<i64>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_sensors(input)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        input
+            .lines()
+            .flat_map(|line| line.split(' '))
+            .filter(|x| x.parse::<i64>().is_err())
+            .map(|x| ParseError::new(input, x, "a number"))
+            .collect()
+    }
+
+    fn part1(sensors: &Self::Input<'_>) -> Answer {
+        sensors
+            .iter()
+            .map(|sensor| process_sensor(sensor, |x| *x.last().unwrap(), |offset, x| x + offset))
+            .sum::<i64>()
+            .into()
+    }
+
+    fn part2(sensors: &Self::Input<'_>) -> Answer {
+        sensors
+            .iter()
+            .map(|sensor| process_sensor(sensor, |x| *x.first().unwrap(), |offset, x| x - offset))
+            .sum::<i64>()
+            .into()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(sensors: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(sensors, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_sensors("0 3 6\n1 3 x 10").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.line_text, "1 3 x 10");
+    }
+}
@@ -0,0 +1,46 @@
+//! Solving a line at a time over bytes, for inputs too big to read in whole. Each history is
+//! differenced in place, in a buffer that's reused for every line.
+
+use common::{
+    bytes::{Cursor, Lines},
+    Answer, ParseError, Part,
+};
+
+/// The value after `history` in part one, or before it in part two, like
+/// [`crate::process_sensor`]. `history` is overwritten with its differences.
+fn extrapolate(part: Part, history: &mut [i64]) -> i64 {
+    let mut len = history.len();
+    let mut sign = 1;
+    let mut value = 0;
+    loop {
+        value += match part {
+            Part::One => history[len - 1],
+            Part::Two => sign * history[0],
+        };
+        sign = -sign;
+        for i in 0..len - 1 {
+            history[i] = history[i + 1] - history[i];
+        }
+        len -= 1;
+        if history[..len].iter().all(|&x| x == 0) {
+            return value;
+        }
+    }
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let mut history = Vec::new();
+    let mut sum = 0i64;
+    while let Some((number, line)) = lines.next_line() {
+        let mut cursor = Cursor::new(number, line);
+        history.clear();
+        while let Some(value) = cursor.next_number() {
+            history.push(value?);
+        }
+        if history.is_empty() {
+            return Err(cursor.error("a number"));
+        }
+        sum += extrapolate(part, &mut history);
+    }
+    Ok(sum.into())
+}
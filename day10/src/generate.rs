@@ -0,0 +1,295 @@
+use std::{collections::BTreeSet, fmt};
+
+use common::{hash::HashMap, rng::Rng, Solution};
+use grid::{Grid, Pos};
+
+use crate::{count_enclosed, farthest_pipe, CellType, Day10, Maze};
+
+const JUNK: [char; 7] = ['|', '-', 'L', 'J', '7', 'F', '.'];
+
+/// A maze whose loop runs around the edge of a region of unit squares, with the squares' corners
+/// as the maze's cells. Any simply connected region without squares that only touch at a corner
+/// has a single loop around it, which is how a maze with exactly one loop is generated.
+#[derive(Clone)]
+pub struct Case {
+    pub region: BTreeSet<(usize, usize)>,
+    /// Empty rows and columns around the loop.
+    pub margin: usize,
+    /// Pipes and ground off the loop. Ones that fall outside the maze or on the loop are left out.
+    pub junk: Vec<((usize, usize), char)>,
+    /// Which of the loop's cells holds `S`.
+    pub start: usize,
+    /// How many rows and columns the maze has at least, if it's to be bigger than the loop and
+    /// margin make it.
+    pub size: Option<(usize, usize)>,
+}
+
+impl Case {
+    /// The loop around the region, as corner positions in order, or `None` if the region's edge
+    /// isn't a single loop.
+    pub(crate) fn outline(&self) -> Option<Vec<(usize, usize)>> {
+        let mut edges = BTreeSet::new();
+        for &(row, col) in &self.region {
+            let sides = [
+                (
+                    row.checked_sub(1).map(|row| (row, col)),
+                    (row, col),
+                    (row, col + 1),
+                ),
+                (Some((row + 1, col)), (row + 1, col), (row + 1, col + 1)),
+                (
+                    col.checked_sub(1).map(|col| (row, col)),
+                    (row, col),
+                    (row + 1, col),
+                ),
+                (Some((row, col + 1)), (row, col + 1), (row + 1, col + 1)),
+            ];
+            for (neighbor, a, b) in sides {
+                if !neighbor.is_some_and(|neighbor| self.region.contains(&neighbor)) {
+                    edges.insert((a, b));
+                }
+            }
+        }
+
+        let mut neighbors = HashMap::<_, Vec<_>>::default();
+        for &(a, b) in &edges {
+            neighbors.entry(a).or_default().push(b);
+            neighbors.entry(b).or_default().push(a);
+        }
+        let first = edges.iter().next()?.0;
+        let mut outline = vec![first];
+        let mut previous = first;
+        let mut current = neighbors[&first][0];
+        while current != first {
+            let next = match *neighbors[&current].as_slice() {
+                [a, b] if a == previous => b,
+                [a, b] if b == previous => a,
+                _ => return None,
+            };
+            outline.push(current);
+            (previous, current) = (current, next);
+        }
+        (outline.len() == edges.len()).then_some(outline)
+    }
+
+    /// Where `S` goes, before the margin is added.
+    fn start_corner(&self, outline: &[(usize, usize)]) -> (usize, usize) {
+        outline[self.start % outline.len()]
+    }
+
+    /// The maze in the puzzle's input format, or `None` if the region doesn't make one loop.
+    pub fn to_input(&self) -> Option<String> {
+        let outline = self.outline()?;
+        let rows = outline.iter().map(|&(row, _)| row).max()? + 1 + 2 * self.margin;
+        let cols = outline.iter().map(|&(_, col)| col).max()? + 1 + 2 * self.margin;
+        let (rows, cols) = match self.size {
+            Some((min_rows, min_cols)) => (rows.max(min_rows), cols.max(min_cols)),
+            None => (rows, cols),
+        };
+        let mut cells = Grid::new(rows, cols, '.');
+
+        for &((row, col), c) in &self.junk {
+            if let Some(cell) = cells.get_mut(Pos { row, col }) {
+                *cell = c;
+            }
+        }
+        let start = self.start_corner(&outline);
+        for (i, &(row, col)) in outline.iter().enumerate() {
+            let before = outline[(i + outline.len() - 1) % outline.len()];
+            let after = outline[(i + 1) % outline.len()];
+            let directions = [before, after]
+                .map(|(r, c)| (r as isize - row as isize, c as isize - col as isize));
+            let c = match directions {
+                [(-1, 0), (1, 0)] | [(1, 0), (-1, 0)] => '|',
+                [(0, -1), (0, 1)] | [(0, 1), (0, -1)] => '-',
+                [(-1, 0), (0, 1)] | [(0, 1), (-1, 0)] => 'L',
+                [(-1, 0), (0, -1)] | [(0, -1), (-1, 0)] => 'J',
+                [(1, 0), (0, -1)] | [(0, -1), (1, 0)] => '7',
+                _ => 'F',
+            };
+            let pos = Pos {
+                row: row + self.margin,
+                col: col + self.margin,
+            };
+            cells[pos] = if (row, col) == start { 'S' } else { c };
+        }
+
+        Some(
+            cells
+                .iter_rows()
+                .map(|row| row.iter().collect::<String>() + "\n")
+                .collect(),
+        )
+    }
+
+    /// Whether the case makes a maze the puzzle could give, with nothing off the loop pointing
+    /// into `S` and making its pipe ambiguous.
+    pub(crate) fn is_valid(&self) -> bool {
+        !self.region.is_empty()
+            && self
+                .to_input()
+                .is_some_and(|input| Day10::parse(&input).is_ok())
+    }
+}
+
+impl fmt::Debug for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_input() {
+            Some(input) => write!(f, "\n{input}"),
+            None => write!(f, "invalid region {:?}", self.region),
+        }
+    }
+}
+
+/// Whether adding `square` keeps the region's edge a single loop: it mustn't touch a square
+/// only at a corner, and the region squares around it must form one unbroken run, since joining
+/// two runs would close off a hole.
+fn can_add(region: &BTreeSet<(usize, usize)>, (row, col): (usize, usize)) -> bool {
+    const RING: [(isize, isize); 8] = [
+        (-1, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+        (1, 0),
+        (1, -1),
+        (0, -1),
+        (-1, -1),
+    ];
+    let ring = RING.map(|(dr, dc)| {
+        let neighbor = (row.checked_add_signed(dr), col.checked_add_signed(dc));
+        matches!(neighbor, (Some(r), Some(c)) if region.contains(&(r, c)))
+    });
+
+    let pinched = (1..8)
+        .step_by(2)
+        .any(|i| ring[i] && !ring[i - 1] && !ring[(i + 1) % 8]);
+    let runs = (0..8).filter(|&i| ring[i] && !ring[(i + 7) % 8]).count();
+    !pinched && runs == 1
+}
+
+/// A region of up to `area` squares within `rows` x `cols`, grown one square at a time from a
+/// random one. Squares are only added while the region's edge stays at least `thinness` times
+/// as long as its area, so higher values grow long thin arms instead of blobs.
+fn grow(
+    rng: &mut Rng,
+    rows: usize,
+    cols: usize,
+    area: usize,
+    thinness: f64,
+) -> BTreeSet<(usize, usize)> {
+    let first = (rng.index(rows), rng.index(cols));
+    let mut region = BTreeSet::from([first]);
+    let mut squares = vec![first];
+    let mut edge = 4;
+    for _ in 0..200 * area {
+        if squares.len() >= area {
+            break;
+        }
+        let (row, col) = squares[rng.index(squares.len())];
+        let (dr, dc) = *rng.choose(&[(-1, 0), (1, 0), (0, -1), (0, 1)]);
+        let (Some(row), Some(col)) = (row.checked_add_signed(dr), col.checked_add_signed(dc))
+        else {
+            continue;
+        };
+        if row >= rows
+            || col >= cols
+            || region.contains(&(row, col))
+            || !can_add(&region, (row, col))
+        {
+            continue;
+        }
+        let neighbors = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter(|&(dr, dc)| {
+                let neighbor = (row.checked_add_signed(dr), col.checked_add_signed(dc));
+                matches!(neighbor, (Some(r), Some(c)) if region.contains(&(r, c)))
+            })
+            .count();
+        let grown = edge + 4 - 2 * neighbors;
+        if (grown as f64) < thinness * (squares.len() + 1) as f64 {
+            continue;
+        }
+        region.insert((row, col));
+        squares.push((row, col));
+        edge = grown;
+    }
+    region
+}
+
+/// Scatters junk over a `height` x `width` maze, putting some at each cell with chance `density`.
+fn scatter(rng: &mut Rng, case: &mut Case, height: usize, width: usize, density: f64) {
+    // Nothing next to `S` may point into it, or its pipe would be ambiguous.
+    let outline = case
+        .outline()
+        .expect("regions are only grown into single loops");
+    let (start_row, start_col) = case.start_corner(&outline);
+    let (start_row, start_col) = (start_row + case.margin, start_col + case.margin);
+    for row in 0..height {
+        for col in 0..width {
+            let next_to_start = row.abs_diff(start_row) + col.abs_diff(start_col) == 1;
+            if !next_to_start && rng.chance(density) {
+                case.junk.push(((row, col), *rng.choose(&JUNK)));
+            }
+        }
+    }
+}
+
+/// A loop around a region of up to `size` x `size` squares, with junk pipes scattered around it.
+pub fn generate_case(rng: &mut Rng, size: usize) -> Case {
+    let size = size.max(1) as i64;
+    let rows = rng.range((size / 2).max(1)..=size) as usize;
+    let cols = rng.range((size / 2).max(1)..=size) as usize;
+    let area = rng.range(((rows * cols) as i64 / 3).max(1)..=(rows * cols) as i64) as usize;
+    let region = grow(rng, rows, cols, area, 0.0);
+
+    let margin = rng.range(0..=2) as usize;
+    let (height, width) = (rows + 1 + 2 * margin, cols + 1 + 2 * margin);
+    let density = rng.range(0..=60) as f64 / 100.0;
+    let mut case = Case {
+        region,
+        margin,
+        junk: Vec::new(),
+        start: rng.index(4 * rows * cols),
+        size: None,
+    };
+    scatter(rng, &mut case, height, width, density);
+    case
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    generate_case(rng, size).to_input().unwrap()
+}
+
+/// A maze the size of `maze`, whose loop is about as long and encloses about as many cells, with
+/// about as much junk around it.
+pub fn lookalike(maze: &Maze, rng: &mut Rng) -> String {
+    let (height, width) = maze.cells.size();
+    let edge = 2 * farthest_pipe(maze).1 as usize;
+    let enclosed = count_enclosed(maze).0;
+    // By Pick's theorem, with the loop's cells as the region's corners.
+    let area = (enclosed + edge / 2).saturating_sub(1).max(1);
+    let (rows, cols) = (
+        height.saturating_sub(1).max(1),
+        width.saturating_sub(1).max(1),
+    );
+    let region = grow(rng, rows, cols, area, edge as f64 / area as f64);
+
+    let pipes = maze
+        .cells
+        .iter()
+        .filter(|(_, cell)| **cell != CellType::Ground)
+        .count();
+    let off_loop = (height * width).saturating_sub(edge).max(1);
+    // Junk is ground a seventh of the time.
+    let density = (pipes.saturating_sub(edge) as f64 / off_loop as f64 * 7.0 / 6.0).min(1.0);
+    let mut case = Case {
+        region,
+        margin: 0,
+        junk: Vec::new(),
+        start: rng.index(4 * rows * cols),
+        size: Some((height, width)),
+    };
+    scatter(rng, &mut case, height, width, density);
+    case.to_input()
+        .expect("regions are only grown into single loops")
+}
@@ -1,12 +1,24 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    hash::Hash,
-    ops::Add,
-};
+use std::{collections::VecDeque, hash::Hash};
 
+use common::{
+    budget,
+    bytes::Lines,
+    differential::Check,
+    hash::{HashMap, HashSet},
+    rng::Rng,
+    Answer, Extras, ParseError, Part, Solution, Value,
+};
 use console::{style, Term};
+use grid::{Grid, Pos, ORTHOGONAL};
 use itertools::Itertools;
 
+mod generate;
+pub mod reference;
+mod stream;
+
+const CELLS: &str = "one of `|-LJ7F.S`";
+const START_UNCONNECTED: &str = "a start position connected to exactly two pipes";
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum CellType {
     Vertical,
@@ -19,86 +31,82 @@ pub enum CellType {
     Start,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Position {
-    pub row: usize,
-    pub col: usize,
-}
-
-pub struct Grid {
-    pub cells: HashMap<Position, CellType>,
-    pub size: (usize, usize),
-    pub start_position: Position,
+pub struct Maze {
+    pub cells: Grid<CellType>,
+    pub start_position: Pos,
 }
 
-impl Grid {
-    pub fn parse(input: &str) -> Self {
-        let mut cells = HashMap::new();
-        let mut start_position = None;
+impl Maze {
+    fn parse_cells(input: &str) -> Result<Grid<CellType>, ParseError> {
+        Grid::parse(input, CELLS, CellType::from_char)
+    }
 
-        for (row, line) in input.lines().enumerate() {
-            for (col, cell) in line.char_indices() {
-                let position = Position { row, col };
-                if cell == 'S' {
-                    start_position = Some(position);
-                } else {
-                    cells.insert(position, CellType::from_char(cell));
-                }
-            }
-        }
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Maze::with_start(
+            Maze::parse_cells(input)?,
+            || ParseError::at_offset(input, input.len(), "a start position `S`"),
+            |pos| ParseError::at_position(input, pos.row, pos.col, START_UNCONNECTED),
+        )
+    }
 
-        let start_position = start_position.expect("No start position found");
-        let grid_size: (usize, usize) =
-            (input.lines().count(), input.lines().next().unwrap().len());
+    /// The maze of `cells`, with the start's pipe worked out from the pipes around it. `missing`
+    /// and `unconnected` give the errors for a maze without a start and for a start that doesn't
+    /// connect to two pipes.
+    fn with_start(
+        mut cells: Grid<CellType>,
+        missing: impl FnOnce() -> ParseError,
+        unconnected: impl FnOnce(Pos) -> ParseError,
+    ) -> Result<Self, ParseError> {
+        let start_position = cells
+            .iter()
+            .find_map(|(pos, cell)| (*cell == CellType::Start).then_some(pos))
+            .ok_or_else(missing)?;
 
-        let start_cell_type = Grid::infer_start_cell_type(&cells, &grid_size, &start_position);
-        cells.insert(start_position.clone(), start_cell_type);
+        let start_cell_type = Maze::infer_start_cell_type(&cells, start_position)
+            .ok_or_else(|| unconnected(start_position))?;
+        cells[start_position] = start_cell_type;
 
-        Grid {
+        Ok(Maze {
             cells,
-            size: grid_size,
             start_position,
-        }
+        })
     }
 
-    fn infer_start_cell_type(
-        cells: &HashMap<Position, CellType>,
-        grid_size: &(usize, usize),
-        start_position: &Position,
-    ) -> CellType {
-        let offsets = Position::offsets()
-            .filter_map(|offset| {
-                let neighbor = (start_position + offset)?;
-                neighbor.is_valid(grid_size).then_some(offset)
-            })
-            .filter(|valid_offset| {
-                let neighbor = (start_position + valid_offset).expect("should be filtered");
-                let is_connected = neighbor
-                    .connected_pipes(&cells[&neighbor])
-                    .any(|x| x == *start_position);
+    fn infer_start_cell_type(cells: &Grid<CellType>, start_position: Pos) -> Option<CellType> {
+        let offsets = ORTHOGONAL
+            .iter()
+            .filter(|&&offset| {
+                let Some(neighbor) = cells.step(start_position, offset) else {
+                    return false;
+                };
+                let is_connected = connected_pipes(cells, neighbor).any(|x| x == start_position);
 
                 is_connected
             })
             .sorted()
             .collect_vec();
         match offsets.as_slice() {
-            [(-1, 0), (1, 0)] => CellType::Vertical,
-            [(0, -1), (0, 1)] => CellType::Horizontal,
-            [(-1, 0), (0, 1)] => CellType::BendNE,
-            [(-1, 0), (0, -1)] => CellType::BendNW,
-            [(0, -1), (1, 0)] => CellType::BendSW,
-            [(0, 1), (1, 0)] => CellType::BendSE,
-            _ => panic!("Invalid start location"),
+            [(-1, 0), (1, 0)] => Some(CellType::Vertical),
+            [(0, -1), (0, 1)] => Some(CellType::Horizontal),
+            [(-1, 0), (0, 1)] => Some(CellType::BendNE),
+            [(-1, 0), (0, -1)] => Some(CellType::BendNW),
+            [(0, -1), (1, 0)] => Some(CellType::BendSW),
+            [(0, 1), (1, 0)] => Some(CellType::BendSE),
+            _ => None,
         }
     }
 
-    pub fn print(&self, highlight: &HashSet<Position>) -> Result<(), std::io::Error> {
+    pub fn connected_pipes(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        connected_pipes(&self.cells, pos)
+    }
+
+    pub fn print(&self, highlight: &HashSet<Pos>) -> Result<(), std::io::Error> {
         let term = Term::stdout();
-        for row in 0..self.size.0 {
+        for (row, cells) in self.cells.iter_rows().enumerate() {
             let mut line = String::new();
-            for col in 0..self.size.1 {
-                let pos = Position { row, col };
-                let c = match self.cells[&pos] {
+            for (col, cell) in cells.iter().enumerate() {
+                let pos = Pos { row, col };
+                let c = match cell {
                     CellType::Vertical => '|',
                     CellType::Horizontal => '-',
                     CellType::BendNE => '⎣',
@@ -122,64 +130,25 @@ impl Grid {
     }
 }
 
-impl Position {
-    fn offsets() -> impl Iterator<Item = &'static (isize, isize)> {
-        const OFFSETS: &[(isize, isize)] = &[(-1, 0), (1, 0), (0, -1), (0, 1)];
-        OFFSETS.iter()
-    }
-
-    pub fn neighbors<'a>(
-        &'a self,
-        grid_size: &'a (usize, usize),
-    ) -> impl Iterator<Item = Position> + 'a {
-        Self::offsets()
-            .map(|(dr, dc)| (self.row as isize + dr, self.col as isize + dc))
-            .filter(|&(row, col)| row >= 0 && col >= 0)
-            .map(|(row, col)| Position {
-                row: row as usize,
-                col: col as usize,
-            })
-            .filter(|position| position.row < grid_size.0 && position.col < grid_size.1)
-    }
-
-    pub fn connected_pipes<'a>(
-        &'a self,
-        cell_type: &CellType,
-    ) -> impl Iterator<Item = Position> + 'a {
-        cell_type
-            .connected_pipe_offsets()
-            .into_iter()
-            .filter_map(move |offset: (isize, isize)| self + &offset)
-    }
-
-    fn is_valid(&self, &(rows, cols): &(usize, usize)) -> bool {
-        self.row < rows && self.col < cols
-    }
-}
-
-impl Add<&(isize, isize)> for &Position {
-    type Output = Option<Position>;
-
-    fn add(self, rhs: &(isize, isize)) -> Self::Output {
-        Some(Position {
-            row: (self.row).checked_add_signed(rhs.0)?,
-            col: self.col.checked_add_signed(rhs.1)?,
-        })
-    }
+fn connected_pipes(cells: &Grid<CellType>, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    cells[pos]
+        .connected_pipe_offsets()
+        .into_iter()
+        .filter_map(move |offset| cells.step(pos, offset))
 }
 
 impl CellType {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '|' => CellType::Vertical,
-            '-' => CellType::Horizontal,
-            'L' => CellType::BendNE,
-            'J' => CellType::BendNW,
-            '7' => CellType::BendSW,
-            'F' => CellType::BendSE,
-            '.' => CellType::Ground,
-            'S' => CellType::Start,
-            _ => panic!("Invalid character: {}", c),
+            '|' => Some(CellType::Vertical),
+            '-' => Some(CellType::Horizontal),
+            'L' => Some(CellType::BendNE),
+            'J' => Some(CellType::BendNW),
+            '7' => Some(CellType::BendSW),
+            'F' => Some(CellType::BendSE),
+            '.' => Some(CellType::Ground),
+            'S' => Some(CellType::Start),
+            _ => None,
         }
     }
 
@@ -197,6 +166,8 @@ impl CellType {
     }
 }
 
+/// Breadth-first search from `start`, returning the first item reached for each visit key. Each
+/// item taken off the queue is a [`budget`] step.
 pub fn bfs<QueueType, VisitedType>(
     start: impl Iterator<Item = QueueType>,
     get_neighbors: impl Fn(&QueueType) -> Vec<QueueType>,
@@ -206,9 +177,10 @@ where
     VisitedType: Eq + Hash,
 {
     let mut queue: VecDeque<QueueType> = VecDeque::from_iter(start);
-    let mut visited: HashMap<VisitedType, QueueType> = HashMap::new();
+    let mut visited: HashMap<VisitedType, QueueType> = HashMap::default();
 
     while let Some(next) = queue.pop_front() {
+        budget::step();
         let key = get_visit_key(&next);
 
         if visited.contains_key(&key) {
@@ -228,3 +200,220 @@ where
 
     visited
 }
+
+fn double_grid(maze: &Maze, pipes: &HashSet<Pos>) -> Maze {
+    let (rows, cols) = maze.cells.size();
+    let mut new_cells = Grid::new(rows * 2, cols * 2, CellType::Ground);
+
+    for &Pos { row, col } in pipes {
+        let pos = Pos {
+            row: row * 2,
+            col: col * 2,
+        };
+        let cell_type = maze.cells[Pos { row, col }].clone();
+
+        // add neighboring pipes
+        for offset in cell_type.connected_pipe_offsets() {
+            if let Some(neighbor) = new_cells.step(pos, offset) {
+                new_cells[neighbor] = CellType::Horizontal; // any cell type is fine
+            }
+        }
+        new_cells[pos] = cell_type;
+    }
+
+    Maze {
+        cells: new_cells,
+        start_position: Pos {
+            row: maze.start_position.row * 2,
+            col: maze.start_position.col * 2,
+        },
+    }
+}
+
+/// The pipe position farthest along the loop from the start, and how many steps away it is.
+pub fn farthest_pipe(maze: &Maze) -> (Pos, i32) {
+    let get_neighbors = |&(pos, step): &(Pos, i32)| {
+        maze.connected_pipes(pos)
+            .map(|pos| (pos, step + 1))
+            .collect_vec()
+    };
+    let get_visit_key = |&(pos, _): &(Pos, i32)| pos;
+
+    let visited = bfs(
+        [(maze.start_position, 0i32)].into_iter(),
+        get_neighbors,
+        get_visit_key,
+    );
+
+    visited
+        .into_values()
+        .max_by_key(|(_, steps)| *steps)
+        .unwrap()
+}
+
+/// Counts the ground cells enclosed by the loop, returned as `(inside_count, ground_count)`.
+pub fn count_enclosed(maze: &Maze) -> (usize, usize) {
+    let maze = {
+        let visited = bfs(
+            [maze.start_position].into_iter(),
+            |&pos| maze.connected_pipes(pos).collect_vec(),
+            |&pos| pos,
+        );
+
+        double_grid(maze, &visited.into_values().collect())
+    };
+    let (rows, cols) = maze.cells.size();
+
+    let grounds_along_edge = maze
+        .cells
+        .iter()
+        .filter(|&(Pos { row, col }, _)| {
+            row == 0 || col == 0 || (row == rows - 1) || (col == cols - 1)
+        })
+        .filter_map(|(pos, cell_type)| (*cell_type == CellType::Ground).then_some(pos));
+
+    let get_neighboring_ground_cells = |&pos: &Pos| {
+        maze.cells
+            .neighbors4(pos)
+            .filter(|&neighbor| maze.cells[neighbor] == CellType::Ground)
+            .collect_vec()
+    };
+
+    let outside_ground_cells: HashSet<Pos> = {
+        let get_visit_key = |&pos: &Pos| pos;
+        let visited = bfs(
+            grounds_along_edge,
+            get_neighboring_ground_cells,
+            get_visit_key,
+        );
+        visited.into_values().collect()
+    };
+
+    let grid_coords = (0..rows).step_by(2).cartesian_product((0..cols).step_by(2));
+    let (ground_count, inside_count) = grid_coords
+        .map(|(row, col)| Pos { row, col })
+        .map(|pos| {
+            let is_ground = maze.cells[pos] == CellType::Ground;
+            let is_inside = !outside_ground_cells.contains(&pos);
+            (is_ground, is_inside)
+        })
+        .fold(
+            (0, 0),
+            |(ground_count, inside_count), (is_ground, is_inside)| {
+                (
+                    ground_count + if is_ground { 1 } else { 0 },
+                    inside_count + if is_ground && is_inside { 1 } else { 0 },
+                )
+            },
+        );
+
+    (inside_count, ground_count)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Maze::parse(input)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let cells = match Maze::parse_cells(input) {
+            Ok(cells) => cells,
+            Err(error) => return vec![error],
+        };
+        let starts = cells
+            .iter()
+            .filter(|(_, cell)| **cell == CellType::Start)
+            .map(|(pos, _)| pos)
+            .collect_vec();
+        match starts.as_slice() {
+            [_, extra @ ..] if !extra.is_empty() => extra
+                .iter()
+                .map(|pos| {
+                    ParseError::at_position(input, pos.row, pos.col, "only one start position `S`")
+                })
+                .collect(),
+            // With no start, or one that doesn't connect to two pipes, parsing says why.
+            _ => Maze::parse(input).err().into_iter().collect(),
+        }
+    }
+
+    fn part1(maze: &Self::Input<'_>) -> Answer {
+        farthest_pipe(maze).1.into()
+    }
+
+    fn part2(maze: &Self::Input<'_>) -> Answer {
+        count_enclosed(maze).0.into()
+    }
+
+    fn extras(maze: &Self::Input<'_>, part: Part) -> Extras {
+        match part {
+            Part::One => {
+                let (farthest, _) = farthest_pipe(maze);
+                let position = vec![("row", farthest.row.into()), ("col", farthest.col.into())];
+                vec![("farthest", Value::Object(position))]
+            }
+            Part::Two => vec![("ground", count_enclosed(maze).1.into())],
+        }
+    }
+
+    fn differentials() -> Vec<Box<dyn Check>> {
+        reference::differentials()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(maze: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(maze, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::differential;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Maze::parse(".....\n.S-7.\n.|x|.").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 3));
+        assert_eq!(error.expected, "one of `|-LJ7F.S`");
+
+        let error = Maze::parse("...\n.F-\n...").err().unwrap();
+        assert_eq!(error.expected, "a start position `S`");
+
+        let error = Maze::parse("...\n.S-\n...").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn test_stream_errors_match_parse_errors() {
+        for input in [
+            ".....\n.S-7.\n.|x|.",
+            "...\n.F-\n...",
+            "...\n.S-\n...",
+            "S-7\n|.|\nL-",
+        ] {
+            let error = Maze::parse(input).err().unwrap();
+            for part in Part::ALL {
+                let streamed = Day10::stream(part, &mut Lines::from_bytes(input)).unwrap();
+                assert_eq!(streamed.err(), Some(error.clone()), "{input:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        differential::assert_all(Day10::differentials());
+    }
+}
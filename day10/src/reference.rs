@@ -0,0 +1,125 @@
+//! Slow but obviously correct versions of this day's solvers, for differential testing.
+
+use common::{
+    differential::{shrink_int, shrink_vec, Check, Differential},
+    hash::HashSet,
+    Solution,
+};
+use grid::Pos;
+
+use crate::{
+    count_enclosed, farthest_pipe,
+    generate::{generate_case, Case},
+    CellType, Day10, Maze,
+};
+
+/// The loop's positions in order, by following the pipes from the start until they lead back.
+pub fn walk_loop(maze: &Maze) -> Vec<Pos> {
+    let mut path = vec![maze.start_position];
+    let mut previous = maze.start_position;
+    let mut current = maze.connected_pipes(maze.start_position).next().unwrap();
+    while current != maze.start_position {
+        path.push(current);
+        let next = maze
+            .connected_pipes(current)
+            .find(|&pos| pos != previous)
+            .unwrap();
+        (previous, current) = (current, next);
+    }
+    path
+}
+
+/// Half the length of the loop.
+pub fn farthest_steps(maze: &Maze) -> usize {
+    walk_loop(maze).len() / 2
+}
+
+/// Scans each row from the left, flipping between outside and inside at every loop pipe that
+/// connects upwards, and counts the other cells passed while inside.
+pub fn count_inside(maze: &Maze) -> usize {
+    let pipes = walk_loop(maze).into_iter().collect::<HashSet<_>>();
+    let mut count = 0;
+    for (row, cells) in maze.cells.iter_rows().enumerate() {
+        let mut inside = false;
+        for (col, cell) in cells.iter().enumerate() {
+            if !pipes.contains(&Pos { row, col }) {
+                count += inside as usize;
+            } else if matches!(
+                cell,
+                CellType::Vertical | CellType::BendNE | CellType::BendNW
+            ) {
+                inside = !inside;
+            }
+        }
+    }
+    count
+}
+
+fn shrink(case: &Case) -> Vec<Case> {
+    let smaller_regions = case.region.iter().map(|square| {
+        let mut region = case.region.clone();
+        region.remove(square);
+        Case {
+            region,
+            ..case.clone()
+        }
+    });
+    let moved_regions = [(1, 0), (0, 1)].into_iter().filter_map(|(up, left)| {
+        let region = case
+            .region
+            .iter()
+            .map(|&(row, col)| Some((row.checked_sub(up)?, col.checked_sub(left)?)))
+            .collect::<Option<_>>()?;
+        Some(Case {
+            region,
+            ..case.clone()
+        })
+    });
+    let less_junk = shrink_vec(&case.junk).into_iter().map(|junk| Case {
+        junk,
+        ..case.clone()
+    });
+    let smaller_margins = shrink_int(case.margin as i64, 0)
+        .into_iter()
+        .map(|margin| Case {
+            margin: margin as usize,
+            ..case.clone()
+        });
+    let earlier_starts = shrink_int(case.start as i64, 0)
+        .into_iter()
+        .map(|start| Case {
+            start: start as usize,
+            ..case.clone()
+        });
+
+    smaller_regions
+        .chain(moved_regions)
+        .chain(less_junk)
+        .chain(smaller_margins)
+        .chain(earlier_starts)
+        .filter(Case::is_valid)
+        .collect()
+}
+
+fn parse(case: &Case) -> Maze {
+    Day10::parse(&case.to_input().unwrap()).unwrap()
+}
+
+pub fn differentials() -> Vec<Box<dyn Check>> {
+    vec![
+        Box::new(Differential {
+            name: "day10 farthest_pipe",
+            generate: |rng| generate_case(rng, 5),
+            shrink,
+            fast: |case| farthest_pipe(&parse(case)).1 as usize,
+            reference: |case| farthest_steps(&parse(case)),
+        }),
+        Box::new(Differential {
+            name: "day10 count_enclosed (doubled-grid flood fill)",
+            generate: |rng| generate_case(rng, 5),
+            shrink,
+            fast: |case| count_enclosed(&parse(case)).0,
+            reference: |case| count_inside(&parse(case)),
+        }),
+    ]
+}
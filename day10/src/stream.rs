@@ -0,0 +1,47 @@
+//! Reading the maze a row at a time from bytes, for inputs too big to read in whole. Finding the
+//! loop takes the whole maze, so it's all kept, but as one small cell per byte of input rather
+//! than the text and then a grid built from it.
+
+use common::{
+    bytes::{self, Lines},
+    Answer, ParseError, Part,
+};
+use grid::{Grid, Pos};
+
+use crate::{count_enclosed, farthest_pipe, CellType, Maze, CELLS, START_UNCONNECTED};
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let mut width = None;
+    let mut cells = Vec::new();
+    // Copies of the line with the first `S`, to point at if it doesn't connect to two pipes, and
+    // of the last line, to point past if there's no `S` at all.
+    let mut start = None;
+    let mut last = (1, Vec::new());
+    while let Some((number, line)) = lines.next_line() {
+        for (col, &cell) in line.iter().enumerate() {
+            let cell = CellType::from_char(char::from(cell))
+                .ok_or_else(|| ParseError::in_line(number, line, col, CELLS))?;
+            if cell == CellType::Start && start.is_none() {
+                start = Some((number, line.to_vec()));
+            }
+            cells.push(cell);
+        }
+        bytes::grid_row(number, line, &mut width)?;
+        last.0 = number;
+        last.1.clear();
+        last.1.extend_from_slice(line);
+    }
+
+    let maze = Maze::with_start(
+        Grid::from_cells(width.unwrap_or(0), cells),
+        || ParseError::in_line(last.0, &last.1, last.1.len(), "a start position `S`"),
+        |Pos { col, .. }| {
+            let (number, line) = start.expect("the maze has a start");
+            ParseError::in_line(number, &line, col, START_UNCONNECTED)
+        },
+    )?;
+    Ok(match part {
+        Part::One => farthest_pipe(&maze).1.into(),
+        Part::Two => count_enclosed(&maze).0.into(),
+    })
+}
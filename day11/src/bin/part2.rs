@@ -1,9 +1,8 @@
-use std::{env::args, fs};
+use std::process::ExitCode;
 
-use day11::get_distance_sum;
+use common::Part;
+use day11::Day11;
 
-fn main() {
-    let input = fs::read_to_string(args().nth(1).unwrap()).unwrap();
-    let result = get_distance_sum(&input, 1_000_000);
-    println!("{result}");
+fn main() -> ExitCode {
+    common::main::<Day11>(Part::Two)
 }
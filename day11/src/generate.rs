@@ -0,0 +1,112 @@
+use std::fmt;
+
+use common::{hash::HashSet, rng::Rng};
+use grid::Grid;
+
+/// An image, and how much empty space expands by.
+#[derive(Clone)]
+pub struct Case {
+    pub image: Vec<Vec<bool>>,
+    pub scaling_factor: usize,
+}
+
+impl Case {
+    /// The image in the puzzle's input format.
+    pub fn to_input(&self) -> String {
+        self.image
+            .iter()
+            .map(|row| {
+                let mut line = row
+                    .iter()
+                    .map(|&c| if c { '#' } else { '.' })
+                    .collect::<String>();
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// Puzzle images have at least two galaxies, so there's a distance to measure.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.image.iter().flatten().filter(|&&c| c).count() >= 2
+    }
+}
+
+impl fmt::Debug for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scaling factor {}\n{}",
+            self.scaling_factor,
+            self.to_input()
+        )
+    }
+}
+
+/// A sparse image of up to `size` x `size` pixels, with at least two galaxies.
+pub fn generate_case(rng: &mut Rng, size: usize) -> Case {
+    let size = size.max(2) as i64;
+    loop {
+        let rows = rng.range((size / 2).max(1)..=size);
+        let cols = rng.range((size / 2).max(1)..=size);
+        let density = rng.range(2..=25) as f64 / 100.0;
+        let image = (0..rows)
+            .map(|_| (0..cols).map(|_| rng.chance(density)).collect())
+            .collect();
+        let case = Case {
+            image,
+            scaling_factor: rng.range(1..=4) as usize,
+        };
+        if case.is_valid() {
+            return case;
+        }
+    }
+}
+
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    generate_case(rng, size).to_input()
+}
+
+/// An image the size of `image` with as many galaxies and as many empty rows and columns, but
+/// different ones.
+pub fn lookalike(image: &Grid<bool>, rng: &mut Rng) -> String {
+    let (rows, cols) = image.size();
+    let galaxies = image.iter().filter(|(_, &galaxy)| galaxy).count();
+    let empty_rows = image.iter_rows().filter(|row| !row.contains(&true)).count();
+    let empty_cols = image
+        .iter_columns()
+        .map(|mut col| col.any(|&galaxy| galaxy))
+        .filter(|&full| !full)
+        .count();
+
+    let mut full_rows = (0..rows).collect::<Vec<_>>();
+    let mut full_cols = (0..cols).collect::<Vec<_>>();
+    rng.shuffle(&mut full_rows);
+    rng.shuffle(&mut full_cols);
+    full_rows.truncate(rows - empty_rows);
+    full_cols.truncate(cols - empty_cols);
+
+    // A galaxy in every row and column that isn't to be empty, then the rest anywhere else in
+    // them.
+    let mut placed = HashSet::default();
+    if !full_rows.is_empty() && !full_cols.is_empty() {
+        for i in 0..full_rows.len().max(full_cols.len()) {
+            placed.insert((
+                full_rows[i % full_rows.len()],
+                full_cols[i % full_cols.len()],
+            ));
+        }
+        while placed.len() < galaxies {
+            placed.insert((*rng.choose(&full_rows), *rng.choose(&full_cols)));
+        }
+    }
+
+    let image = (0..rows)
+        .map(|row| (0..cols).map(|col| placed.contains(&(row, col))).collect())
+        .collect();
+    Case {
+        image,
+        scaling_factor: 1,
+    }
+    .to_input()
+}
@@ -1,37 +1,37 @@
+use common::{bytes::Lines, differential::Check, rng::Rng, Answer, ParseError, Part, Solution};
+use grid::Grid;
 use itertools::Itertools;
 
-fn find_positions(grid: &[Vec<bool>]) -> Vec<(usize, usize)> {
+mod generate;
+pub mod reference;
+mod stream;
+
+const TOO_FEW: &str = "at least two galaxies `#`";
+
+fn find_positions(grid: &Grid<bool>) -> Vec<(usize, usize)> {
     grid.iter()
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.iter()
-                .enumerate()
-                .filter(|(_, &c)| c)
-                .map(move |(col, _)| (row, col))
-        })
+        .filter(|(_, &c)| c)
+        .map(|(pos, _)| (pos.row, pos.col))
         .collect_vec()
 }
 
-pub fn get_distance_sum(input: &str, scaling_factor: i64) -> i64 {
-    let grid: Vec<Vec<bool>> = input
-        .lines()
-        .map(|line| line.chars().map(|c| c == '#').collect_vec())
-        .collect_vec();
-
+pub fn get_distance_sum(grid: &Grid<bool>, scaling_factor: i64) -> i64 {
     let rows_to_double = grid
-        .iter()
+        .iter_rows()
         .enumerate()
         .filter(|(_, row)| !row.iter().any(|&c| c))
         .map(|(i, _)| i)
         .collect_vec();
 
-    let cols_to_double = (0..grid[0].len())
-        .filter(|&col| !grid.iter().any(|row| row[col]))
+    let cols_to_double = grid
+        .iter_columns()
+        .enumerate()
+        .filter_map(|(i, mut col)| (!col.any(|&c| c)).then_some(i))
         .collect_vec();
 
-    let positions = find_positions(&grid);
+    let positions = find_positions(grid);
 
-    let result = (0..(positions.len() - 1))
+    let result = (0..positions.len().saturating_sub(1))
         .flat_map(|i| ((i + 1)..positions.len()).map(move |j| (i, j)))
         .map(|(i, j)| {
             let (r1, c1) = positions[i];
@@ -51,3 +51,70 @@ pub fn get_distance_sum(input: &str, scaling_factor: i64) -> i64 {
         .sum::<i64>();
     result
 }
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input<'a> = Grid<bool>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let image = Grid::parse(input, "`#` or `.`", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        match image.iter().filter(|(_, &galaxy)| galaxy).count() {
+            0 | 1 => Err(ParseError::at_offset(input, input.len(), TOO_FEW)),
+            _ => Ok(image),
+        }
+    }
+
+    fn part1(grid: &Self::Input<'_>) -> Answer {
+        get_distance_sum(grid, 2).into()
+    }
+
+    fn part2(grid: &Self::Input<'_>) -> Answer {
+        get_distance_sum(grid, 1_000_000).into()
+    }
+
+    fn differentials() -> Vec<Box<dyn Check>> {
+        reference::differentials()
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate::generate(rng, size))
+    }
+
+    fn lookalike(image: &Self::Input<'_>, rng: &mut Rng) -> Option<String> {
+        Some(generate::lookalike(image, rng))
+    }
+
+    fn stream(part: Part, lines: &mut Lines<'_>) -> Option<Result<Answer, ParseError>> {
+        Some(stream::solve(part, lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::differential;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_needs_two_galaxies() {
+        for input in ["", "...", "..#\n..."] {
+            let error = Day11::parse(input).unwrap_err();
+            assert_eq!(error.expected, TOO_FEW);
+            for part in Part::ALL {
+                let streamed = Day11::stream(part, &mut Lines::from_bytes(input)).unwrap();
+                assert_eq!(streamed, Err(error.clone()), "{input:?}");
+            }
+        }
+        assert!(Day11::parse("#.#").is_ok());
+    }
+
+    #[test]
+    fn test_matches_reference() {
+        differential::assert_all(Day11::differentials());
+    }
+}
@@ -0,0 +1,100 @@
+//! Slow but obviously correct versions of this day's solvers, for differential testing.
+
+use common::{
+    differential::{shrink_int, Check, Differential},
+    Solution,
+};
+use itertools::Itertools;
+
+use crate::{
+    generate::{generate_case, Case},
+    get_distance_sum, Day11,
+};
+
+/// Inserts `scaling_factor - 1` copies of every empty row and column, then measures the distance
+/// between each pair of galaxies in the expanded image.
+pub fn distance_sum(image: &[Vec<bool>], scaling_factor: usize) -> i64 {
+    let expand_rows = |rows: &[Vec<bool>]| {
+        rows.iter()
+            .flat_map(|row| {
+                let copies = if row.contains(&true) {
+                    1
+                } else {
+                    scaling_factor
+                };
+                std::iter::repeat_n(row.clone(), copies)
+            })
+            .collect_vec()
+    };
+    let transpose = |rows: &[Vec<bool>]| {
+        (0..rows[0].len())
+            .map(|col| rows.iter().map(|row| row[col]).collect_vec())
+            .collect_vec()
+    };
+    let expanded = transpose(&expand_rows(&transpose(&expand_rows(image))));
+
+    let galaxies = expanded
+        .iter()
+        .enumerate()
+        .flat_map(|(row, cells)| cells.iter().positions(|&c| c).map(move |col| (row, col)))
+        .collect_vec();
+    galaxies
+        .iter()
+        .tuple_combinations()
+        .map(|(a, b)| (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as i64)
+        .sum()
+}
+
+fn shrink(case: &Case) -> Vec<Case> {
+    let image = &case.image;
+    let (rows, cols) = (image.len(), image[0].len());
+    let with_image = |image| Case { image, ..*case };
+
+    let without_rows = (0..rows).filter(|_| rows > 1).map(|i| {
+        let mut image = image.clone();
+        image.remove(i);
+        with_image(image)
+    });
+    let without_cols = (0..cols).filter(|_| cols > 1).map(|i| {
+        let mut image = image.clone();
+        for row in &mut image {
+            row.remove(i);
+        }
+        with_image(image)
+    });
+    let fewer_galaxies = (0..rows)
+        .cartesian_product(0..cols)
+        .filter(|&(row, col)| image[row][col])
+        .map(|(row, col)| {
+            let mut image = image.clone();
+            image[row][col] = false;
+            with_image(image)
+        });
+    let less_expansion =
+        shrink_int(case.scaling_factor as i64, 1)
+            .into_iter()
+            .map(|scaling_factor| Case {
+                image: image.clone(),
+                scaling_factor: scaling_factor as usize,
+            });
+
+    without_rows
+        .chain(without_cols)
+        .chain(fewer_galaxies)
+        .chain(less_expansion)
+        .filter(Case::is_valid)
+        .collect()
+}
+
+pub fn differentials() -> Vec<Box<dyn Check>> {
+    vec![Box::new(Differential {
+        name: "day11 get_distance_sum",
+        generate: |rng| generate_case(rng, 8),
+        shrink,
+        fast: |case| {
+            let grid = Day11::parse(&case.to_input()).unwrap();
+            get_distance_sum(&grid, case.scaling_factor as i64)
+        },
+        reference: |case| distance_sum(&case.image, case.scaling_factor),
+    })]
+}
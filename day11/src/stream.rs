@@ -0,0 +1,74 @@
+//! Solving a row at a time over bytes, for inputs too big to read in whole. Distances are split
+//! into their row and column parts, which add up separately: the rows as they're read, and the
+//! columns from a count of galaxies per column, so only one number per column is kept.
+
+use common::{
+    bytes::{self, Lines},
+    Answer, ParseError, Part,
+};
+
+use crate::TOO_FEW;
+
+/// Adds up the distances along one axis between galaxies, given how many galaxies are at each
+/// position along it in order, with each empty position counting `scaling_factor` times.
+#[derive(Debug, Default)]
+struct Axis {
+    /// Where the next position is, once empty ones before it have been expanded.
+    at: i64,
+    galaxies: i64,
+    /// The sum of the expanded positions of every galaxy so far.
+    positions: i64,
+    distances: i64,
+}
+
+impl Axis {
+    fn add(&mut self, galaxies: i64, scaling_factor: i64) {
+        if galaxies == 0 {
+            self.at += scaling_factor;
+            return;
+        }
+        self.distances += galaxies * (self.galaxies * self.at - self.positions);
+        self.galaxies += galaxies;
+        self.positions += galaxies * self.at;
+        self.at += 1;
+    }
+}
+
+pub fn solve(part: Part, lines: &mut Lines<'_>) -> Result<Answer, ParseError> {
+    let scaling_factor = match part {
+        Part::One => 2,
+        Part::Two => 1_000_000,
+    };
+    let mut width = None;
+    let mut columns = Vec::new();
+    let mut rows = Axis::default();
+    // A copy of the last line, to point past if there aren't enough galaxies.
+    let mut last = (1, Vec::new());
+    while let Some((number, line)) = lines.next_line() {
+        if let Some(offset) = line.iter().position(|&cell| cell != b'#' && cell != b'.') {
+            return Err(ParseError::in_line(number, line, offset, "`#` or `.`"));
+        }
+        bytes::grid_row(number, line, &mut width)?;
+        columns.resize(line.len(), 0);
+        let mut galaxies = 0;
+        for (count, &cell) in columns.iter_mut().zip(line) {
+            if cell == b'#' {
+                *count += 1;
+                galaxies += 1;
+            }
+        }
+        rows.add(galaxies, scaling_factor);
+        last.0 = number;
+        last.1.clear();
+        last.1.extend_from_slice(line);
+    }
+    if rows.galaxies < 2 {
+        return Err(ParseError::in_line(last.0, &last.1, last.1.len(), TOO_FEW));
+    }
+
+    let mut cols = Axis::default();
+    for &galaxies in &columns {
+        cols.add(galaxies, scaling_factor);
+    }
+    Ok((rows.distances + cols.distances).into())
+}
@@ -0,0 +1,317 @@
+use std::ops::{Index, IndexMut};
+
+use common::{parse, ParseError};
+
+/// Offsets to the four orthogonal neighbours: up, down, left, right.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Offsets to all eight neighbours, including diagonals.
+pub const ADJACENT: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn new(row: usize, col: usize) -> Self {
+        Pos { row, col }
+    }
+
+    /// Moves by `(rows, cols)`, or `None` if that would go above or left of the origin. Use
+    /// [`Grid::step`] to also check the far edges.
+    pub fn offset(&self, (rows, cols): (isize, isize)) -> Option<Pos> {
+        Some(Pos {
+            row: self.row.checked_add_signed(rows)?,
+            col: self.col.checked_add_signed(cols)?,
+        })
+    }
+}
+
+/// A rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// A grid `cols` wide from its cells, row by row, which must fill every row they start.
+    pub fn from_cells(cols: usize, cells: Vec<T>) -> Self {
+        let rows = cells.len().checked_div(cols).unwrap_or(0);
+        assert_eq!(
+            rows * cols,
+            cells.len(),
+            "cells don't fill {cols}-wide rows"
+        );
+        Grid { cells, rows, cols }
+    }
+
+    /// Builds a grid from a char map, one line per row, converting each character with `cell`.
+    /// Characters that `cell` rejects and rows of the wrong width are reported as parse errors,
+    /// with `expected` describing the valid characters.
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let rows = parse::finish(input, parse::grid(expected, cell)(input))?;
+        Ok(Grid {
+            rows: rows.len(),
+            cols: rows.first().map_or(0, Vec::len),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(rows, cols)`
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.rows && pos.col < self.cols
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[self.index_of(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if !self.contains(pos) {
+            return None;
+        }
+        let index = self.index_of(pos);
+        Some(&mut self.cells[index])
+    }
+
+    /// Moves `pos` by `offset`, or `None` if that leaves the grid.
+    pub fn step(&self, pos: Pos, offset: (isize, isize)) -> Option<Pos> {
+        pos.offset(offset).filter(|&pos| self.contains(pos))
+    }
+
+    /// The up-to-four orthogonal neighbours of `pos` that are inside the grid.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |&offset| self.step(pos, offset))
+    }
+
+    /// The up-to-eight neighbours of `pos`, including diagonals, that are inside the grid.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ADJACENT
+            .iter()
+            .filter_map(move |&offset| self.step(pos, offset))
+    }
+
+    /// Every position in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let cols = self.cols;
+        (0..self.rows).flat_map(move |row| (0..cols).map(move |col| Pos { row, col }))
+    }
+
+    /// Every cell with its position, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.cols, "column {col} out of bounds");
+        self.cells.iter().skip(col).step_by(self.cols)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.cols.max(1))
+    }
+
+    pub fn iter_columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.cols).map(|col| self.column(col))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+
+    /// Builds a `rows` x `cols` grid, filling each cell from the position it maps back to.
+    fn remap(&self, rows: usize, cols: usize, source: impl Fn(Pos) -> Pos) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| Pos { row, col }))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Grid { cells, rows, cols }
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.cols, self.rows, |Pos { row, col }| Pos {
+            row: col,
+            col: row,
+        })
+    }
+
+    /// Rotates a quarter turn clockwise, so the first column becomes the first row, reversed.
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let rows = self.rows;
+        self.remap(self.cols, self.rows, |Pos { row, col }| Pos {
+            row: rows - 1 - col,
+            col: row,
+        })
+    }
+
+    /// Rotates a quarter turn counter-clockwise, so the last column becomes the first row.
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cols = self.cols;
+        self.remap(self.cols, self.rows, |Pos { row, col }| Pos {
+            row: col,
+            col: cols - 1 - row,
+        })
+    }
+
+    fn index_of(&self, pos: Pos) -> usize {
+        pos.row * self.cols + pos.col
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a {:?} grid", self.size()))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let size = self.size();
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside a {size:?} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(input: &str) -> Grid<char> {
+        Grid::parse(input, "any character", Some).unwrap()
+    }
+
+    fn render(grid: &Grid<char>) -> Vec<String> {
+        grid.iter_rows().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = chars("ab\ncd\nef");
+        assert_eq!(grid.size(), (3, 2));
+        assert_eq!(grid[Pos::new(2, 1)], 'f');
+        assert_eq!(grid.get(Pos::new(3, 0)), None);
+        assert_eq!(grid.get(Pos::new(0, 2)), None);
+
+        let mut grid = grid;
+        *grid.get_mut(Pos::new(0, 1)).unwrap() = 'x';
+        assert_eq!(grid[Pos::new(0, 1)], 'x');
+        assert_eq!(grid.get_mut(Pos::new(usize::MAX, usize::MAX)), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Grid::parse("ab\nc", "any character", Some).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "a row of 2 cells");
+
+        let error = Grid::parse("..\n.x", "`.`", |c| (c == '.').then_some(())).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = chars("...\n...\n...");
+        assert_eq!(grid.neighbors4(Pos::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbors8(Pos::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbors4(Pos::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8(Pos::new(1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbors8(Pos::new(2, 2)).collect::<Vec<_>>(),
+            vec![Pos::new(1, 1), Pos::new(1, 2), Pos::new(2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = chars("abc\ndef");
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(
+            grid.iter_columns()
+                .map(|col| col.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = chars("abc\ndef");
+        assert_eq!(render(&grid.transpose()), vec!["ad", "be", "cf"]);
+        assert_eq!(render(&grid.rotate_clockwise()), vec!["da", "eb", "fc"]);
+        assert_eq!(
+            render(&grid.rotate_counterclockwise()),
+            vec!["cf", "be", "ad"]
+        );
+        assert_eq!(
+            grid.rotate_clockwise().rotate_counterclockwise(),
+            grid.clone()
+        );
+    }
+}